@@ -0,0 +1,973 @@
+use crate::api::errors::{BitbucketBaseUrlUndefined, BitbucketTokenUndefined};
+use crate::api::github_service::{
+    FindFirstPrOfCommitResult, GetCommitResult, GetCommitsSinceResult, GetIssueResult,
+    GithubService, SearchPullRequestResult,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// `GithubService` backed by the Bitbucket Server / Data Center REST API (1.0)
+///
+/// `owner_name` is used as the Bitbucket project key and `repo_name` as the repository slug.
+/// Bitbucket Server has no PR labels, so they are always empty.
+#[derive(Clone)]
+pub struct BitbucketServiceImpl {
+    pub base_url: String,
+    pub token: String,
+}
+
+/// Number of commits requested per page
+const PAGE_LIMIT: u64 = 100;
+
+/// Number of pages of commits searched by `search_commits`
+const SEARCHED_COMMIT_PAGES: usize = 50;
+
+/// Number of pages of merged PRs searched by `search_pull_requests`
+const SEARCHED_PULL_REQUEST_PAGES: usize = 5;
+
+impl BitbucketServiceImpl {
+    /// `base_url` is the Bitbucket server, e.g. `https://bitbucket.example.com`
+    pub fn new(base_url: Option<&str>, token: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        match (base_url, token) {
+            (None, _) => Err(Box::new(BitbucketBaseUrlUndefined)),
+            (_, None) => Err(Box::new(BitbucketTokenUndefined)),
+            (Some(base_url), Some(token)) => Ok(Self {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                token: token.to_string(),
+            }),
+        }
+    }
+
+    fn repo_url(&self, owner_name: &str, repo_name: &str) -> String {
+        format!(
+            "{}/rest/api/1.0/projects/{}/repos/{}",
+            self.base_url, owner_name, repo_name
+        )
+    }
+
+    async fn get(&self, url: &str) -> anyhow::Result<String> {
+        let response = reqwest::Client::new()
+            .get(url)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!("Error fetching {}: {}", url, response.status()));
+        }
+
+        Ok(response.text().await?)
+    }
+
+    async fn get_json(&self, url: &str) -> anyhow::Result<serde_json::Value> {
+        let body = self.get(url).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Get the name of the user who merged the PR from its activities
+    async fn get_pr_merged_by(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        pr_id: u64,
+    ) -> anyhow::Result<String> {
+        let activities = self
+            .get_json(&format!(
+                "{}/pull-requests/{}/activities?limit={}",
+                self.repo_url(owner_name, repo_name),
+                pr_id,
+                PAGE_LIMIT
+            ))
+            .await?;
+
+        Ok(activities["values"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .find(|activity| activity["action"].as_str() == Some("MERGED"))
+            .and_then(|activity| activity["user"]["name"].as_str())
+            .unwrap_or("")
+            .to_string())
+    }
+}
+
+fn parse_timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    value.as_i64().and_then(DateTime::from_timestamp_millis)
+}
+
+#[async_trait]
+impl GithubService for BitbucketServiceImpl {
+    async fn get_commit(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        commit_sha: &str,
+        with_pr: bool,
+    ) -> anyhow::Result<GetCommitResult> {
+        let commit = self
+            .get_json(&format!(
+                "{}/commits/{}",
+                self.repo_url(owner_name, repo_name),
+                commit_sha
+            ))
+            .await?;
+
+        let date_time = parse_timestamp(&commit["committerTimestamp"])
+            .ok_or(anyhow!("Commit {} has no date", commit_sha))?;
+        let author_email = commit["author"]["emailAddress"].as_str().unwrap_or("");
+        let message = commit["message"].as_str().unwrap_or("");
+
+        let pr_info = if with_pr {
+            self.find_first_pr_of_commit(owner_name, repo_name, commit_sha)
+                .await?
+        } else {
+            FindFirstPrOfCommitResult::default()
+        };
+
+        Ok(GetCommitResult {
+            date_time,
+            author_email: author_email.to_string(),
+            sha: commit_sha.to_string(),
+            full_message: message.to_string(),
+            pr_title: pr_info.pr_title,
+            pr_body: pr_info.pr_body,
+            pr_number: pr_info.pr_number,
+            pr_url: pr_info.pr_url,
+            pr_labels: pr_info.pr_labels,
+            pr_merged_by: pr_info.pr_merged_by,
+            pr_reviewers: pr_info.pr_reviewers,
+            pr_head_branch: pr_info.pr_head_branch,
+            pr_merged_at: pr_info.pr_merged_at,
+        })
+    }
+
+    /// Bitbucket cannot filter commits by date, so pages are fetched (newest first)
+    /// until a commit older than `date_time` shows up
+    async fn get_commits_since(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        date_time: DateTime<Utc>,
+    ) -> anyhow::Result<GetCommitsSinceResult> {
+        let mut commit_shas: Vec<String> = vec![];
+        let mut commit_parents: HashMap<String, Vec<String>> = HashMap::new();
+        let mut start: u64 = 0;
+
+        // Commits are listed in topological order, so a commit older than the cutoff may come
+        // before newer commits of a merged branch. Stop only after a whole page of older commits
+        loop {
+            let page = self
+                .get_json(&format!(
+                    "{}/commits?limit={}&start={}",
+                    self.repo_url(owner_name, repo_name),
+                    PAGE_LIMIT,
+                    start
+                ))
+                .await?;
+
+            let mut is_page_older = true;
+            for commit in page["values"].as_array().unwrap_or(&vec![]) {
+                let committed_at = parse_timestamp(&commit["committerTimestamp"]);
+                if committed_at.is_some_and(|committed_at| committed_at < date_time) {
+                    continue;
+                }
+                is_page_older = false;
+
+                let sha = match commit["id"].as_str() {
+                    Some(sha) => sha.to_string(),
+                    None => continue,
+                };
+                let parents = commit["parents"]
+                    .as_array()
+                    .unwrap_or(&vec![])
+                    .iter()
+                    .filter_map(|parent| parent["id"].as_str())
+                    .map(|parent| parent.to_string())
+                    .collect();
+                commit_shas.push(sha.clone());
+                commit_parents.insert(sha, parents);
+            }
+            if is_page_older {
+                break;
+            }
+
+            match (page["isLastPage"].as_bool(), page["nextPageStart"].as_u64()) {
+                (Some(false), Some(next_page_start)) => start = next_page_start,
+                _ => break,
+            }
+        }
+
+        Ok(GetCommitsSinceResult {
+            commit_shas,
+            commit_parents,
+        })
+    }
+
+    async fn get_contents(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<String> {
+        self.get(&format!(
+            "{}/raw/{}",
+            self.repo_url(owner_name, repo_name),
+            file_path
+        ))
+        .await
+    }
+
+    async fn get_contents_at(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+        git_ref: &str,
+    ) -> anyhow::Result<String> {
+        self.get(&format!(
+            "{}/raw/{}?at={}",
+            self.repo_url(owner_name, repo_name),
+            file_path,
+            urlencoding::encode(git_ref)
+        ))
+        .await
+    }
+
+    async fn get_file_history(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<Vec<GetCommitResult>> {
+        let mut history: Vec<GetCommitResult> = vec![];
+        let mut start: u64 = 0;
+
+        loop {
+            let page = self
+                .get_json(&format!(
+                    "{}/commits?path={}&limit={}&start={}",
+                    self.repo_url(owner_name, repo_name),
+                    urlencoding::encode(file_path),
+                    PAGE_LIMIT,
+                    start
+                ))
+                .await?;
+
+            for commit in page["values"].as_array().unwrap_or(&vec![]) {
+                let sha = commit["id"].as_str().unwrap_or("");
+                history.push(GetCommitResult {
+                    date_time: parse_timestamp(&commit["committerTimestamp"])
+                        .ok_or(anyhow!("Commit {} has no date", sha))?,
+                    author_email: commit["author"]["emailAddress"]
+                        .as_str()
+                        .unwrap_or("")
+                        .to_string(),
+                    sha: sha.to_string(),
+                    full_message: commit["message"].as_str().unwrap_or("").to_string(),
+                    ..Default::default()
+                });
+            }
+
+            match (page["isLastPage"].as_bool(), page["nextPageStart"].as_u64()) {
+                (Some(false), Some(next_page_start)) => start = next_page_start,
+                _ => break,
+            }
+        }
+
+        Ok(history)
+    }
+
+    async fn search_commits(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        // Bitbucket Server has no commit search, walk the latest commits of the default branch
+        let text = text.to_lowercase();
+        let mut commit_shas: Vec<String> = vec![];
+        let mut start: u64 = 0;
+
+        for _ in 0..SEARCHED_COMMIT_PAGES {
+            let page = self
+                .get_json(&format!(
+                    "{}/commits?limit={}&start={}",
+                    self.repo_url(owner_name, repo_name),
+                    PAGE_LIMIT,
+                    start
+                ))
+                .await?;
+
+            for commit in page["values"].as_array().unwrap_or(&vec![]) {
+                let message = commit["message"].as_str().unwrap_or("").to_lowercase();
+                if let (true, Some(sha)) = (message.contains(&text), commit["id"].as_str()) {
+                    commit_shas.push(sha.to_string());
+                }
+            }
+
+            match (page["isLastPage"].as_bool(), page["nextPageStart"].as_u64()) {
+                (Some(false), Some(next_page_start)) => start = next_page_start,
+                _ => break,
+            }
+        }
+
+        Ok(commit_shas)
+    }
+
+    async fn search_pull_requests(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<SearchPullRequestResult>> {
+        // `filterText` matches titles and descriptions but not branches, filter the PRs here
+        let mut pull_requests: Vec<SearchPullRequestResult> = vec![];
+        let mut start: u64 = 0;
+
+        for _ in 0..SEARCHED_PULL_REQUEST_PAGES {
+            let page = self
+                .get_json(&format!(
+                    "{}/pull-requests?state=MERGED&order=NEWEST&limit={}&start={}",
+                    self.repo_url(owner_name, repo_name),
+                    PAGE_LIMIT,
+                    start
+                ))
+                .await?;
+
+            pull_requests.extend(
+                page["values"]
+                    .as_array()
+                    .unwrap_or(&vec![])
+                    .iter()
+                    .map(|pull_request| SearchPullRequestResult {
+                        title: pull_request["title"].as_str().unwrap_or("").to_string(),
+                        head_branch: pull_request["fromRef"]["displayId"]
+                            .as_str()
+                            .unwrap_or("")
+                            .to_string(),
+                        merge_commit_sha: pull_request["properties"]["mergeCommit"]["id"]
+                            .as_str()
+                            .unwrap_or("")
+                            .to_string(),
+                    })
+                    .filter(|pull_request| {
+                        !pull_request.merge_commit_sha.is_empty() && pull_request.mentions(text)
+                    }),
+            );
+
+            match (page["isLastPage"].as_bool(), page["nextPageStart"].as_u64()) {
+                (Some(false), Some(next_page_start)) => start = next_page_start,
+                _ => break,
+            }
+        }
+
+        Ok(pull_requests)
+    }
+
+    async fn is_ancestor(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        ancestor_sha: &str,
+        descendant_sha: &str,
+    ) -> anyhow::Result<bool> {
+        // Commits reachable from `ancestor_sha` but not from `descendant_sha`, none if it is an
+        // ancestor
+        let compare = self
+            .get_json(&format!(
+                "{}/compare/commits?from={}&to={}&limit=1",
+                self.repo_url(owner_name, repo_name),
+                ancestor_sha,
+                descendant_sha
+            ))
+            .await?;
+
+        Ok(compare["values"]
+            .as_array()
+            .is_some_and(|values| values.is_empty()))
+    }
+
+    async fn find_first_pr_of_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+    ) -> anyhow::Result<FindFirstPrOfCommitResult> {
+        let pull_requests = self
+            .get_json(&format!(
+                "{}/commits/{}/pull-requests",
+                self.repo_url(owner, repo),
+                commit_sha
+            ))
+            .await?;
+
+        let pull_request = match pull_requests["values"]
+            .as_array()
+            .and_then(|pull_requests| {
+                pull_requests
+                    .iter()
+                    .min_by_key(|pr| pr["createdDate"].as_i64().unwrap_or(i64::MAX))
+            }) {
+            Some(pull_request) => pull_request,
+            None => return Ok(FindFirstPrOfCommitResult::default()),
+        };
+
+        let pr_number = pull_request["id"].as_u64();
+        let pr_reviewers: Vec<String> = pull_request["reviewers"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter(|reviewer| reviewer["status"].as_str() == Some("APPROVED"))
+            .filter_map(|reviewer| reviewer["user"]["name"].as_str())
+            .map(|name| name.to_string())
+            .collect();
+        let pr_merged_by = match pr_number {
+            Some(pr_id) if pull_request["state"].as_str() == Some("MERGED") => {
+                self.get_pr_merged_by(owner, repo, pr_id).await?
+            }
+            _ => "".to_string(),
+        };
+
+        Ok(FindFirstPrOfCommitResult {
+            pr_number,
+            pr_url: pull_request["links"]["self"][0]["href"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            pr_title: pull_request["title"].as_str().unwrap_or("").to_string(),
+            pr_body: pull_request["description"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            pr_labels: vec![],
+            pr_merged_by,
+            pr_reviewers,
+            pr_head_branch: pull_request["fromRef"]["displayId"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            pr_merged_at: match pull_request["state"].as_str() {
+                Some("MERGED") => parse_timestamp(&pull_request["closedDate"]),
+                _ => None,
+            },
+        })
+    }
+
+    async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> anyhow::Result<GetIssueResult> {
+        Err(anyhow!(
+            "Bitbucket Server has no issues, cannot fetch {}/{}#{}",
+            owner,
+            repo,
+            issue_number
+        ))
+    }
+
+    async fn get_tag_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch tag {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_latest_release_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch the latest release of {}/{}",
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_release_url(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch release {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        _commit_sha: &str,
+        _message: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot create tag {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        _name: &str,
+        _body: &str,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot create release {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot fetch the default branch of {}/{}",
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        _from_branch: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot create branch {} of {}/{}",
+            branch,
+            owner,
+            repo
+        ))
+    }
+
+    async fn update_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        _branch: &str,
+        file_path: &str,
+        _contents: &str,
+        _message: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot update {} of {}/{}",
+            file_path,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        _base: &str,
+        _title: &str,
+        _body: &str,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot open a PR of {} in {}/{}",
+            head,
+            owner,
+            repo
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_commit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/rest/api/1.0/projects/FOO/repos/foo/commits/123")
+            .match_header("Authorization", "Bearer test")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "id": "123",
+                    "author": {"emailAddress": "test@test.com"},
+                    "committerTimestamp": 1627776000000,
+                    "message": "FOO-1: Test commit message"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .get_commit("FOO", "foo", "123", false)
+            .await
+            .unwrap();
+
+        assert_eq!(result.sha, "123");
+        assert_eq!(result.author_email, "test@test.com");
+        assert_eq!(result.full_message, "FOO-1: Test commit message");
+        assert_eq!(
+            result.date_time,
+            "2021-08-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_commits_since() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_first_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=0",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "ccc", "committerTimestamp": 1627948800000, "parents": [{"id": "bbb"}, {"id": "zzz"}]}
+                    ],
+                    "isLastPage": false,
+                    "nextPageStart": 1
+                }"#,
+            )
+            .create_async()
+            .await;
+        let _m_second_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=1",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "bbb", "committerTimestamp": 1627776000000, "parents": [{"id": "aaa"}]},
+                        {"id": "aaa", "committerTimestamp": 1627689600000, "parents": []},
+                        {"id": "ddd", "committerTimestamp": 1627862400000, "parents": [{"id": "aaa"}]}
+                    ],
+                    "isLastPage": true
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .get_commits_since("FOO", "foo", "2021-08-01T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        // `ddd` is listed after the older `aaa` but is still newer than the cutoff
+        assert_eq!(result.commit_shas, vec!["ccc", "bbb", "ddd"]);
+        assert_eq!(result.commit_parents["ccc"], vec!["bbb", "zzz"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_commits_since_stops_after_a_page_of_older_commits() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_first_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=0",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "ccc", "committerTimestamp": 1627948800000, "parents": [{"id": "bbb"}]}
+                    ],
+                    "isLastPage": false,
+                    "nextPageStart": 1
+                }"#,
+            )
+            .create_async()
+            .await;
+        let _m_second_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=1",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "bbb", "committerTimestamp": 1627689600000, "parents": [{"id": "aaa"}]}
+                    ],
+                    "isLastPage": false,
+                    "nextPageStart": 2
+                }"#,
+            )
+            .create_async()
+            .await;
+        let m_third_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=2",
+            )
+            .expect(0)
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .get_commits_since("FOO", "foo", "2021-08-01T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(result.commit_shas, vec!["ccc"]);
+        m_third_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_search_commits_and_is_ancestor() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_commits = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits?limit=100&start=0",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "ccc", "message": "foo-123: Fix login"},
+                        {"id": "bbb", "message": "BAR-1: Add logout"},
+                        {"id": "aaa", "message": "FOO-123: Add login"}
+                    ],
+                    "isLastPage": true
+                }"#,
+            )
+            .create_async()
+            .await;
+        let _m_ancestor = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/compare/commits?from=aaa&to=ccc&limit=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"values": [], "isLastPage": true}"#)
+            .create_async()
+            .await;
+        let _m_not_ancestor = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/compare/commits?from=ccc&to=aaa&limit=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"values": [{"id": "ccc"}], "isLastPage": false}"#)
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+
+        assert_eq!(
+            bitbucket
+                .search_commits("FOO", "foo", "FOO-123")
+                .await
+                .unwrap(),
+            vec!["ccc", "aaa"]
+        );
+        assert!(bitbucket
+            .is_ancestor("FOO", "foo", "aaa", "ccc")
+            .await
+            .unwrap());
+        assert!(!bitbucket
+            .is_ancestor("FOO", "foo", "ccc", "aaa")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_search_pull_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/pull-requests?state=MERGED&order=NEWEST&limit=100&start=0",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"title": "Add login", "fromRef": {"displayId": "feature/FOO-123"}, "properties": {"mergeCommit": {"id": "aaa"}}},
+                        {"title": "Unrelated", "fromRef": {"displayId": "unrelated"}, "properties": {"mergeCommit": {"id": "bbb"}}}
+                    ],
+                    "isLastPage": true
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .search_pull_requests("FOO", "foo", "FOO-123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].merge_commit_sha, "aaa");
+    }
+
+    #[tokio::test]
+    async fn test_get_file_history_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_first_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/config/commits?path=apps%2Ffoo%2Fconfig.json&limit=100&start=0",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "bbb", "committerTimestamp": 1627862400000, "author": {"emailAddress": "me@example.com"}, "message": "Promote foo"}
+                    ],
+                    "isLastPage": false,
+                    "nextPageStart": 1
+                }"#,
+            )
+            .create_async()
+            .await;
+        let _m_second_page = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/config/commits?path=apps%2Ffoo%2Fconfig.json&limit=100&start=1",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [
+                        {"id": "aaa", "committerTimestamp": 1627776000000, "author": {"emailAddress": "you@example.com"}, "message": "Add foo"}
+                    ],
+                    "isLastPage": true
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let history = bitbucket
+            .get_file_history("FOO", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        let shas: Vec<&str> = history.iter().map(|commit| commit.sha.as_str()).collect();
+        assert_eq!(shas, vec!["bbb", "aaa"]);
+        assert_eq!(history[1].author_email, "you@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_get_contents() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/config/raw/apps/foo/config.json",
+            )
+            .with_status(200)
+            .with_body(r#"{"identify": "foo123"}"#)
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .get_contents("FOO", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        assert_eq!(result, r#"{"identify": "foo123"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_find_first_pr_of_commit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/commits/123/pull-requests",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "values": [{
+                        "id": 7,
+                        "state": "MERGED",
+                        "createdDate": 1627776000000,
+                        "title": "FOO-123: created something",
+                        "description": "FOO-123: created something detailed",
+                        "reviewers": [
+                            {"user": {"name": "bob"}, "status": "APPROVED"},
+                            {"user": {"name": "carol"}, "status": "NEEDS_WORK"}
+                        ],
+                        "links": {"self": [{"href": "https://bitbucket.example.com/projects/FOO/repos/foo/pull-requests/7"}]}
+                    }]
+                }"#,
+            )
+            .create_async()
+            .await;
+        let _m_activities = server
+            .mock(
+                "GET",
+                "/rest/api/1.0/projects/FOO/repos/foo/pull-requests/7/activities?limit=100",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"values": [
+                    {"action": "COMMENTED", "user": {"name": "bob"}},
+                    {"action": "MERGED", "user": {"name": "merger"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let bitbucket = BitbucketServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = bitbucket
+            .find_first_pr_of_commit("FOO", "foo", "123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.pr_number, Some(7));
+        assert_eq!(
+            result.pr_url,
+            "https://bitbucket.example.com/projects/FOO/repos/foo/pull-requests/7"
+        );
+        assert_eq!(result.pr_title, "FOO-123: created something");
+        assert_eq!(result.pr_body, "FOO-123: created something detailed");
+        assert!(result.pr_labels.is_empty());
+        assert_eq!(result.pr_merged_by, "merger");
+        assert_eq!(result.pr_reviewers, vec!["bob"]);
+    }
+}
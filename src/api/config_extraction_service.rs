@@ -1,6 +1,11 @@
 use crate::api::github_service::GithubService;
 use anyhow::anyhow;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+
+const CONFIG_REPO_OWNER: &str = "jrumjantsev"; // TODO: get owner from the config
+const CONFIG_REPO_NAME: &str = "config";
 
 #[async_trait]
 pub trait ConfigExtractionService: Send + Sync {
@@ -9,6 +14,52 @@ pub trait ConfigExtractionService: Send + Sync {
         service_name: &str,
         env: &str,
     ) -> anyhow::Result<ExtractCommitShaResult>;
+
+    /// Environments of the service, in the order of the config file
+    async fn list_envs(&self, _service_name: &str) -> anyhow::Result<Vec<String>> {
+        Err(anyhow!(
+            "Listing environments is not supported by this config repository"
+        ))
+    }
+
+    /// Compute the config change deploying `commit_sha` of the service to the env
+    async fn plan_promotion(
+        &self,
+        _service_name: &str,
+        _env: &str,
+        _commit_sha: &str,
+    ) -> anyhow::Result<Promotion> {
+        Err(anyhow!(
+            "Promotions are not supported by this config repository"
+        ))
+    }
+
+    /// Commit the promotion on a new branch of the config repository and open a PR of it
+    /// Returns the web URL of the PR
+    async fn open_promotion_pr(
+        &self,
+        _promotion: &Promotion,
+        _title: &str,
+        _body: &str,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Promotions are not supported by this config repository"
+        ))
+    }
+
+    /// Deployments of the service to the env recorded in the history of its config file,
+    /// newest first
+    ///
+    /// A deployment is a revision of the config file that changed the image tag of the env.
+    async fn deployment_history(
+        &self,
+        _service_name: &str,
+        _env: &str,
+    ) -> anyhow::Result<Vec<Deployment>> {
+        Err(anyhow!(
+            "Deployment history is not supported by this config repository"
+        ))
+    }
 }
 
 pub struct GithubConfigExtractionServiceImpl {
@@ -26,6 +77,53 @@ pub struct ExtractCommitShaResult {
     pub commit_sha: String,
 }
 
+/// Revision of the config file deploying a new image tag
+#[derive(Debug, Clone, Default)]
+pub struct Deployment {
+    /// Commit of the config repository
+    pub config_sha: String,
+    pub date_time: DateTime<Utc>,
+    /// Who merged the config change, its author if it was not merged with a PR
+    pub merged_by: String,
+    pub image_tag: String,
+    /// Commit of the service deployed
+    pub commit_sha: String,
+}
+
+/// Change of the image tag of a service in an env
+#[derive(Debug, Clone, Default)]
+pub struct Promotion {
+    /// Path of the config file in the config repository
+    pub file_path: String,
+    /// Branch the change is committed to
+    pub branch: String,
+    pub from_image_tag: String,
+    pub to_image_tag: String,
+    /// Config file with the new image tag
+    pub contents: String,
+}
+
+impl Promotion {
+    /// Whether the commit is deployed already
+    pub fn is_noop(&self) -> bool {
+        self.from_image_tag == self.to_image_tag
+    }
+}
+
+/// Image tag with its trailing commit SHA replaced, e.g. `foo-web-1a2b3c4` -> `foo-web-5d6e7f8`
+///
+/// The new SHA is abbreviated to the length of the current one, so that the tag format is kept.
+/// It is never abbreviated to less than 7 characters.
+pub fn image_tag_with_sha(image_tag: &str, commit_sha: &str) -> String {
+    let (prefix, current_sha) = match image_tag.rsplit_once('-') {
+        Some((prefix, current_sha)) => (format!("{}-", prefix), current_sha),
+        None => (String::new(), image_tag),
+    };
+    let sha_length = current_sha.len().max(7).min(commit_sha.len());
+
+    format!("{}{}", prefix, &commit_sha[..sha_length])
+}
+
 #[async_trait]
 impl ConfigExtractionService for GithubConfigExtractionServiceImpl {
     async fn extract_commit_sha(
@@ -33,8 +131,8 @@ impl ConfigExtractionService for GithubConfigExtractionServiceImpl {
         service_name: &str,
         env: &str,
     ) -> anyhow::Result<ExtractCommitShaResult> {
-        let owner = "jrumjantsev".into(); // TODO: get owner from the config
-        let repo = "config".into();
+        let owner = CONFIG_REPO_OWNER;
+        let repo = CONFIG_REPO_NAME;
         let metafile = format!("apps/{}/config.json", service_name);
 
         let commit_contents = self
@@ -67,18 +165,203 @@ impl ConfigExtractionService for GithubConfigExtractionServiceImpl {
                         panic!();
                     };
 
-                let commit_sha = image_tag_value.split('-').last().unwrap().to_string();
+                let commit_sha = image_tag_value.split('-').next_back().unwrap().to_string();
 
                 Ok(ExtractCommitShaResult { commit_sha })
             }
             Err(e) => Err(anyhow!(e.to_string())),
         }
     }
+
+    async fn list_envs(&self, service_name: &str) -> anyhow::Result<Vec<String>> {
+        let file_path = format!("apps/{}/config.json", service_name);
+        let contents = self
+            .github_service
+            .get_contents(CONFIG_REPO_OWNER, CONFIG_REPO_NAME, &file_path)
+            .await?;
+        let contents_json: serde_json::Value = serde_json::from_str(&contents)?;
+        let path_to_envs = format!("/service/{}/env", service_name);
+
+        Ok(contents_json
+            .pointer(&path_to_envs)
+            .and_then(|envs| envs.as_object())
+            .ok_or(anyhow!(
+                "The specified path does not exist. Path {}",
+                path_to_envs
+            ))?
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    async fn plan_promotion(
+        &self,
+        service_name: &str,
+        env: &str,
+        commit_sha: &str,
+    ) -> anyhow::Result<Promotion> {
+        let file_path = format!("apps/{}/config.json", service_name);
+        let contents = self
+            .github_service
+            .get_contents(CONFIG_REPO_OWNER, CONFIG_REPO_NAME, &file_path)
+            .await?;
+        let mut contents_json: serde_json::Value = serde_json::from_str(&contents)?;
+
+        let path_to_image_tag_value = format!("/service/{}/env/{}/imageTag", service_name, env);
+        let image_tag_value =
+            contents_json
+                .pointer_mut(&path_to_image_tag_value)
+                .ok_or(anyhow!(
+                    "The specified path does not exist. Path {}",
+                    path_to_image_tag_value
+                ))?;
+        let from_image_tag = image_tag_value
+            .as_str()
+            .ok_or(anyhow!("{} is not a string", path_to_image_tag_value))?
+            .to_string();
+        let to_image_tag = image_tag_with_sha(&from_image_tag, commit_sha);
+        *image_tag_value = serde_json::Value::String(to_image_tag.clone());
+
+        let mut new_contents = serde_json::to_string_pretty(&contents_json)?;
+        if contents.ends_with('\n') {
+            new_contents.push('\n');
+        }
+
+        Ok(Promotion {
+            branch: format!("deppy/promote-{}-{}-{}", service_name, env, to_image_tag),
+            file_path,
+            from_image_tag,
+            to_image_tag,
+            contents: new_contents,
+        })
+    }
+
+    async fn open_promotion_pr(
+        &self,
+        promotion: &Promotion,
+        title: &str,
+        body: &str,
+    ) -> anyhow::Result<String> {
+        let base = self
+            .github_service
+            .get_default_branch(CONFIG_REPO_OWNER, CONFIG_REPO_NAME)
+            .await?;
+        self.github_service
+            .create_branch(
+                CONFIG_REPO_OWNER,
+                CONFIG_REPO_NAME,
+                &promotion.branch,
+                &base,
+            )
+            .await?;
+        self.github_service
+            .update_file(
+                CONFIG_REPO_OWNER,
+                CONFIG_REPO_NAME,
+                &promotion.branch,
+                &promotion.file_path,
+                &promotion.contents,
+                title,
+            )
+            .await?;
+
+        self.github_service
+            .create_pull_request(
+                CONFIG_REPO_OWNER,
+                CONFIG_REPO_NAME,
+                &promotion.branch,
+                &base,
+                title,
+                body,
+            )
+            .await
+    }
+
+    async fn deployment_history(
+        &self,
+        service_name: &str,
+        env: &str,
+    ) -> anyhow::Result<Vec<Deployment>> {
+        let file_path = format!("apps/{}/config.json", service_name);
+        let path_to_image_tag_value = format!("/service/{}/env/{}/imageTag", service_name, env);
+        let revisions = self
+            .github_service
+            .get_file_history(CONFIG_REPO_OWNER, CONFIG_REPO_NAME, &file_path)
+            .await?;
+        let revision_contents = try_join_all(revisions.iter().map(|revision| {
+            self.github_service.get_contents_at(
+                CONFIG_REPO_OWNER,
+                CONFIG_REPO_NAME,
+                &file_path,
+                &revision.sha,
+            )
+        }))
+        .await?;
+
+        // Walk the revisions from the oldest, keeping the ones that changed the image tag.
+        // Revisions before the env was added or with an invalid file are skipped.
+        let mut deployments: Vec<Deployment> = vec![];
+        for (revision, contents) in revisions.iter().zip(revision_contents).rev() {
+            let image_tag = serde_json::from_str::<serde_json::Value>(&contents)
+                .ok()
+                .and_then(|contents_json| {
+                    contents_json
+                        .pointer(&path_to_image_tag_value)
+                        .and_then(|value| value.as_str())
+                        .map(|image_tag| image_tag.to_string())
+                });
+            let image_tag = match image_tag {
+                Some(image_tag) => image_tag,
+                None => continue,
+            };
+            if deployments
+                .last()
+                .is_some_and(|deployment| deployment.image_tag == image_tag)
+            {
+                continue;
+            }
+
+            deployments.push(Deployment {
+                config_sha: revision.sha.clone(),
+                date_time: revision.date_time,
+                merged_by: revision.author_email.clone(),
+                commit_sha: image_tag.split('-').next_back().unwrap().to_string(),
+                image_tag,
+            });
+        }
+        deployments.reverse();
+
+        for deployment in deployments.iter_mut() {
+            let pr_info = self
+                .github_service
+                .find_first_pr_of_commit(
+                    CONFIG_REPO_OWNER,
+                    CONFIG_REPO_NAME,
+                    &deployment.config_sha,
+                )
+                .await;
+            match pr_info {
+                Ok(pr_info) if !pr_info.pr_merged_by.is_empty() => {
+                    deployment.merged_by = pr_info.pr_merged_by
+                }
+                Ok(_) => {}
+                Err(err) => eprintln!(
+                    "Error fetching PR of config commit {}: {:?}",
+                    deployment.config_sha, err
+                ),
+            }
+        }
+
+        Ok(deployments)
+    }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::github_service::MockGithubService;
+    use crate::api::github_service::{
+        FindFirstPrOfCommitResult, GetCommitResult, MockGithubService,
+    };
     use futures::FutureExt;
     use mockall::predicate::*;
 
@@ -102,4 +385,133 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().commit_sha, "foo123");
     }
+
+    #[tokio::test]
+    async fn unit_test_list_envs() {
+        let response_payload = r#"{"service": {"foo": {"env": {"staging": {"imageTag": "foo-1"}, "prod": {"imageTag": "foo-2"}, "dev": {"imageTag": "foo-3"}}}}}"#;
+        let mut github_service = MockGithubService::new();
+        github_service
+            .expect_get_contents()
+            .times(1)
+            .returning(|_, _, _| async { Ok(response_payload.to_string()) }.boxed());
+
+        let service = GithubConfigExtractionServiceImpl::new(Box::new(github_service));
+
+        assert_eq!(
+            service.list_envs("foo").await.unwrap(),
+            vec!["staging", "prod", "dev"]
+        );
+    }
+
+    #[test]
+    fn test_image_tag_with_sha() {
+        assert_eq!(
+            image_tag_with_sha("foo-web-1a2b3c4", "5d6e7f8a9b0c"),
+            "foo-web-5d6e7f8"
+        );
+        assert_eq!(image_tag_with_sha("foo123", "5d6e7f8a9b0c"), "5d6e7f8");
+        assert_eq!(
+            image_tag_with_sha("release-1a2b3c4d5e", "5d6e7f8a9b0c"),
+            "release-5d6e7f8a9b"
+        );
+    }
+
+    #[tokio::test]
+    async fn unit_test_plan_promotion() {
+        let response_payload = r#"{
+  "service": {
+    "foo": {
+      "env": {
+        "prod": {
+          "replicas": 3,
+          "imageTag": "foo-1a2b3c4"
+        }
+      }
+    }
+  }
+}
+"#;
+        let mut github_service = MockGithubService::new();
+        github_service
+            .expect_get_contents()
+            .times(1)
+            .returning(|_, _, _| async { Ok(response_payload.to_string()) }.boxed());
+
+        let service = GithubConfigExtractionServiceImpl::new(Box::new(github_service));
+        let promotion = service
+            .plan_promotion("foo", "prod", "5d6e7f8a9b0c")
+            .await
+            .unwrap();
+
+        assert_eq!(promotion.file_path, "apps/foo/config.json");
+        assert_eq!(promotion.branch, "deppy/promote-foo-prod-foo-5d6e7f8");
+        assert_eq!(promotion.from_image_tag, "foo-1a2b3c4");
+        assert_eq!(promotion.to_image_tag, "foo-5d6e7f8");
+        // Only the image tag changes, the key order and formatting are kept
+        assert_eq!(
+            promotion.contents,
+            response_payload.replace("foo-1a2b3c4", "foo-5d6e7f8")
+        );
+    }
+
+    #[tokio::test]
+    async fn unit_test_deployment_history() {
+        let revision = |sha: &str, date: &str| GetCommitResult {
+            sha: sha.to_string(),
+            date_time: date.parse().unwrap(),
+            author_email: "me@example.com".to_string(),
+            ..Default::default()
+        };
+        let revisions = vec![
+            revision("c4", "2026-10-04T10:00:00Z"),
+            revision("c3", "2026-10-03T10:00:00Z"),
+            revision("c2", "2026-10-02T10:00:00Z"),
+            revision("c1", "2026-10-01T10:00:00Z"),
+        ];
+        let mut github_service = MockGithubService::new();
+        github_service
+            .expect_get_file_history()
+            .with(eq("jrumjantsev"), eq("config"), eq("apps/foo/config.json"))
+            .times(1)
+            .returning(move |_, _, _| {
+                let revisions = revisions.clone();
+                async move { Ok(revisions) }.boxed()
+            });
+        github_service
+            .expect_get_contents_at()
+            .times(4)
+            .returning(|_, _, _, git_ref| {
+                let contents = match git_ref {
+                    // Only the dev env changed
+                    "c4" => r#"{"service": {"foo": {"env": {"dev": {"imageTag": "foo-ccc"}, "prod": {"imageTag": "foo-bbb"}}}}}"#,
+                    "c3" => r#"{"service": {"foo": {"env": {"dev": {"imageTag": "foo-bbb"}, "prod": {"imageTag": "foo-bbb"}}}}}"#,
+                    "c2" => r#"{"service": {"foo": {"env": {"dev": {"imageTag": "foo-aaa"}, "prod": {"imageTag": "foo-aaa"}}}}}"#,
+                    _ => r#"{"service": {"foo": {"env": {"dev": {"imageTag": "foo-aaa"}}}}}"#,
+                };
+                async move { Ok(contents.to_string()) }.boxed()
+            });
+        github_service
+            .expect_find_first_pr_of_commit()
+            .returning(|_, _, sha| {
+                let pr_merged_by = if sha == "c3" { "octocat" } else { "" }.to_string();
+                async move {
+                    Ok(FindFirstPrOfCommitResult {
+                        pr_merged_by,
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+
+        let service = GithubConfigExtractionServiceImpl::new(Box::new(github_service));
+        let deployments = service.deployment_history("foo", "prod").await.unwrap();
+
+        assert_eq!(deployments.len(), 2);
+        assert_eq!(deployments[0].config_sha, "c3");
+        assert_eq!(deployments[0].commit_sha, "bbb");
+        assert_eq!(deployments[0].merged_by, "octocat");
+        assert_eq!(deployments[1].config_sha, "c2");
+        assert_eq!(deployments[1].image_tag, "foo-aaa");
+        assert_eq!(deployments[1].merged_by, "me@example.com");
+    }
 }
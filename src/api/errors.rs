@@ -18,3 +18,66 @@ impl std::fmt::Display for GitHubTokenUndefined {
     }
 }
 impl Error for GitHubTokenUndefined {}
+
+#[derive(Debug)]
+pub struct GitLabBaseUrlUndefined;
+impl std::fmt::Display for GitLabBaseUrlUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "GitLab base URL is undefined")
+    }
+}
+impl Error for GitLabBaseUrlUndefined {}
+
+#[derive(Debug)]
+pub struct GitLabTokenUndefined;
+impl std::fmt::Display for GitLabTokenUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "GitLab token is undefined")
+    }
+}
+impl Error for GitLabTokenUndefined {}
+
+#[derive(Debug)]
+pub struct BitbucketBaseUrlUndefined;
+impl std::fmt::Display for BitbucketBaseUrlUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Bitbucket base URL is undefined")
+    }
+}
+impl Error for BitbucketBaseUrlUndefined {}
+
+#[derive(Debug)]
+pub struct BitbucketTokenUndefined;
+impl std::fmt::Display for BitbucketTokenUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Bitbucket token is undefined")
+    }
+}
+impl Error for BitbucketTokenUndefined {}
+
+#[derive(Debug)]
+pub struct LinearTokenUndefined;
+impl std::fmt::Display for LinearTokenUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Linear API key is undefined")
+    }
+}
+impl Error for LinearTokenUndefined {}
+
+#[derive(Debug)]
+pub struct YouTrackBaseUrlUndefined;
+impl std::fmt::Display for YouTrackBaseUrlUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "YouTrack base URL is undefined")
+    }
+}
+impl Error for YouTrackBaseUrlUndefined {}
+
+#[derive(Debug)]
+pub struct YouTrackTokenUndefined;
+impl std::fmt::Display for YouTrackTokenUndefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "YouTrack token is undefined")
+    }
+}
+impl Error for YouTrackTokenUndefined {}
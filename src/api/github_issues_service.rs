@@ -0,0 +1,203 @@
+use crate::api::github_service::{GetIssueResult, GithubService};
+use crate::api::issue_tracker_service::{IssueInfo, IssueTrackerService};
+use async_trait::async_trait;
+use futures::future::join_all;
+
+/// `IssueTrackerService` backed by the issues of a source control repository
+///
+/// Keys are `#N` references, resolved against the default repository, or `owner/repo#N` references.
+pub struct GithubIssuesServiceImpl {
+    github_service: Box<dyn GithubService>,
+    /// `owner/repo` that `#N` references point to
+    default_repo: String,
+    /// Labels marking an issue as ready
+    ready_labels: Vec<String>,
+    /// Milestones whose issues are ready
+    ready_milestones: Vec<String>,
+}
+
+impl GithubIssuesServiceImpl {
+    /// When neither ready labels nor ready milestones are given, closed issues are ready
+    pub fn new(
+        github_service: Box<dyn GithubService>,
+        default_repo: &str,
+        ready_labels: Vec<String>,
+        ready_milestones: Vec<String>,
+    ) -> Self {
+        GithubIssuesServiceImpl {
+            github_service,
+            default_repo: default_repo.to_string(),
+            ready_labels,
+            ready_milestones,
+        }
+    }
+
+    /// Split `#N` or `owner/repo#N` into owner, repository and issue number
+    fn parse_key<'k>(&'k self, key: &'k str) -> Option<(&'k str, &'k str, u64)> {
+        let (repo, number) = key.rsplit_once('#')?;
+        let repo = if repo.is_empty() {
+            self.default_repo.as_str()
+        } else {
+            repo
+        };
+        let (owner, repo) = repo.split_once('/')?;
+
+        Some((owner, repo, number.parse().ok()?))
+    }
+
+    fn is_ready(&self, issue: &GetIssueResult) -> bool {
+        if self.ready_labels.is_empty() && self.ready_milestones.is_empty() {
+            return issue.state == "closed";
+        }
+
+        let has_ready_label = issue.labels.iter().any(|label| {
+            self.ready_labels
+                .iter()
+                .any(|ready_label| ready_label.eq_ignore_ascii_case(label))
+        });
+        let in_ready_milestone = issue.milestone.as_ref().is_some_and(|milestone| {
+            self.ready_milestones
+                .iter()
+                .any(|ready_milestone| ready_milestone == milestone)
+        });
+
+        has_ready_label || in_ready_milestone
+    }
+}
+
+#[async_trait]
+impl IssueTrackerService for GithubIssuesServiceImpl {
+    async fn get_issues(&self, issue_keys: Vec<String>) -> Result<Vec<IssueInfo>, String> {
+        if issue_keys.is_empty() {
+            return Err("No issue keys provided".to_string());
+        }
+
+        let issue_futures = issue_keys.iter().map(|key| async move {
+            let (owner, repo, number) = self
+                .parse_key(key)
+                .ok_or(format!("Invalid issue reference {}", key))?;
+            self.github_service
+                .get_issue(owner, repo, number)
+                .await
+                .map(|issue| (key, issue))
+                .map_err(|err| err.to_string())
+        });
+
+        Ok(join_all(issue_futures)
+            .await
+            .into_iter()
+            .filter_map(|result| match result {
+                Ok((key, issue)) => Some((key, issue)),
+                Err(err) => {
+                    eprintln!("Error fetching issue: {}", err);
+                    None
+                }
+            })
+            .filter(|(_, issue)| !issue.is_pull_request)
+            .map(|(key, issue)| IssueInfo {
+                key: key.to_string(),
+                summary: issue.title.clone(),
+                status: issue.state.clone(),
+                assignee: issue.assignee.clone(),
+                fix_versions: issue.milestone.iter().cloned().collect(),
+                labels: issue.labels.clone(),
+                ready: self.is_ready(&issue),
+                ..Default::default()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::github_service::MockGithubService;
+    use futures::FutureExt;
+    use mockall::predicate::eq;
+
+    #[tokio::test]
+    async fn test_get_issues() {
+        let mut github_service = MockGithubService::new();
+        github_service
+            .expect_get_issue()
+            .with(eq("jrumjantsev"), eq("foo"), eq(12))
+            .returning(|_, _, _| {
+                async {
+                    Ok(GetIssueResult {
+                        state: "open".to_string(),
+                        labels: vec!["Ready".to_string()],
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+        github_service
+            .expect_get_issue()
+            .with(eq("acme"), eq("lib"), eq(3))
+            .returning(|_, _, _| {
+                async {
+                    Ok(GetIssueResult {
+                        state: "closed".to_string(),
+                        milestone: Some("v2".to_string()),
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+        github_service
+            .expect_get_issue()
+            .with(eq("jrumjantsev"), eq("foo"), eq(42))
+            .returning(|_, _, _| {
+                async {
+                    Ok(GetIssueResult {
+                        is_pull_request: true,
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+
+        let service = GithubIssuesServiceImpl::new(
+            Box::new(github_service),
+            "jrumjantsev/foo",
+            vec!["ready".to_string()],
+            vec!["v1".to_string()],
+        );
+        let issues = service
+            .get_issues(vec![
+                "#12".to_string(),
+                "acme/lib#3".to_string(),
+                "#42".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].key, "#12");
+        assert_eq!(issues[0].status, "open");
+        assert!(issues[0].ready);
+        assert_eq!(issues[1].key, "acme/lib#3");
+        assert!(!issues[1].ready);
+    }
+
+    #[test]
+    fn test_closed_issues_are_ready_by_default() {
+        let service = GithubIssuesServiceImpl::new(
+            Box::new(MockGithubService::new()),
+            "jrumjantsev/foo",
+            vec![],
+            vec![],
+        );
+
+        assert!(service.is_ready(&GetIssueResult {
+            state: "closed".to_string(),
+            ..Default::default()
+        }));
+        assert!(!service.is_ready(&GetIssueResult {
+            state: "open".to_string(),
+            ..Default::default()
+        }));
+        assert_eq!(service.parse_key("#7"), Some(("jrumjantsev", "foo", 7)));
+        assert_eq!(service.parse_key("FOO-7"), None);
+    }
+}
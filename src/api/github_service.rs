@@ -1,13 +1,20 @@
 use crate::api::errors::{GitHubBaseUrlUndefined, GitHubTokenUndefined};
 use anyhow::anyhow;
 use async_trait::async_trait;
+use base64::prelude::*;
 use chrono::{DateTime, Utc};
-use octocrab::models::repos::RepoCommit;
-use octocrab::{Octocrab, Page};
+use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 
-#[derive(Debug)]
+/// Number of commits requested per page
+const COMMITS_PER_PAGE: usize = 100;
+
+/// Number of the most recently closed PRs searched by `search_pull_requests`
+const SEARCHED_PULL_REQUESTS: usize = 500;
+
+#[derive(Debug, Clone, Default)]
 pub struct GetCommitResult {
     pub date_time: DateTime<Utc>,
     pub author_email: String,
@@ -15,6 +22,15 @@ pub struct GetCommitResult {
     pub full_message: String,
     pub pr_title: String,
     pub pr_body: String,
+    pub pr_number: Option<u64>,
+    pub pr_url: String,
+    pub pr_labels: Vec<String>,
+    pub pr_merged_by: String,
+    pub pr_reviewers: Vec<String>,
+    /// Name of the branch the PR was opened from
+    pub pr_head_branch: String,
+    /// When the PR was merged, `None` if it is not merged or there is no PR
+    pub pr_merged_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,9 +55,90 @@ struct Author {
     email: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct GetCommitsSinceResult {
     pub commit_shas: Vec<String>,
+    /// Parent SHAs of every commit, the first parent comes first
+    pub commit_parents: HashMap<String, Vec<String>>,
+}
+
+impl GetCommitsSinceResult {
+    /// Follow the first parents starting from the newest commit
+    ///
+    /// Skips the commits brought in by merges, leaving one entry per merged PR.
+    /// Expects the commits to be ordered from the newest to the oldest.
+    pub fn first_parent_shas(&self) -> Vec<String> {
+        let mut first_parent_shas: Vec<String> = vec![];
+        let mut current = self.commit_shas.first();
+
+        while let Some(sha) = current {
+            if !self.commit_shas.contains(sha) || first_parent_shas.contains(sha) {
+                break;
+            }
+            first_parent_shas.push(sha.clone());
+            current = self
+                .commit_parents
+                .get(sha)
+                .and_then(|parents| parents.first());
+        }
+
+        first_parent_shas
+    }
+
+    /// Commits after `from_sha` up to and including `to_sha`, newest first
+    ///
+    /// SHAs may be abbreviated. Fails if `to_sha` is not among the commits or `from_sha` is not
+    /// among the commits older than `to_sha`.
+    pub fn shas_between(&self, from_sha: &str, to_sha: &str) -> anyhow::Result<Vec<String>> {
+        let to_index = self
+            .commit_shas
+            .iter()
+            .position(|sha| sha.starts_with(to_sha))
+            .ok_or_else(|| anyhow!("Commit {} not found in the commits walked", to_sha))?;
+        let from_index = self.commit_shas[to_index..]
+            .iter()
+            .position(|sha| sha.starts_with(from_sha))
+            .map(|index| to_index + index)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Commit {} not found in the commits walked before {}",
+                    from_sha,
+                    to_sha
+                )
+            })?;
+
+        Ok(self.commit_shas[to_index..from_index].to_vec())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchPullRequestResult {
+    pub title: String,
+    /// Name of the branch the PR was opened from
+    pub head_branch: String,
+    /// Commit the PR landed as on the target branch, i.e. the merge, squash or last rebased commit
+    pub merge_commit_sha: String,
+}
+
+impl SearchPullRequestResult {
+    /// Whether the title or the head branch contains a text, ignoring case
+    pub fn mentions(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.title.to_lowercase().contains(&text) || self.head_branch.to_lowercase().contains(&text)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GetIssueResult {
+    pub title: String,
+    /// Login of the first assignee, empty if unassigned
+    pub assignee: String,
+    /// `open` or `closed`
+    pub state: String,
+    pub labels: Vec<String>,
+    pub milestone: Option<String>,
+    /// Pull requests are issues too, e.g. the `(#42)` suffix of squash-merged commits
+    pub is_pull_request: bool,
 }
 
 #[cfg(test)]
@@ -59,6 +156,7 @@ pub trait GithubService: Sync + Send {
     /// - Full message
     /// - PR title (if `with_pr` is true, additional API request)
     /// - PR body (if `with_pr` is true, additional API request)
+    /// - PR number, URL, labels, merged-by, approving reviewers and head branch (if `with_pr` is true)
     async fn get_commit(
         &self,
         owner_name: &str,
@@ -69,7 +167,7 @@ pub trait GithubService: Sync + Send {
 
     /// Get the commits since a given date-time
     ///
-    /// Returns a list of commit SHAs
+    /// Returns a list of commit SHAs (newest first) and their parents
     async fn get_commits_since(
         &self,
         owner_name: &str,
@@ -91,15 +189,150 @@ pub trait GithubService: Sync + Send {
         file_path: &str,
     ) -> anyhow::Result<String>;
 
+    /// Get the contents of a file at a commit, branch or tag
+    async fn get_contents_at(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+        git_ref: &str,
+    ) -> anyhow::Result<String>;
+
+    /// Get the commits that changed a file, newest first, without PR details
+    async fn get_file_history(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<Vec<GetCommitResult>>;
+
+    /// Find the commits of the default branch whose message mentions a text, newest first
+    async fn search_commits(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<String>>;
+
+    /// Find the merged PRs whose title or head branch mentions a text, newest first
+    ///
+    /// Only the most recently merged PRs are searched.
+    async fn search_pull_requests(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<SearchPullRequestResult>>;
+
+    /// Whether a commit is an ancestor of (or the same as) another commit
+    async fn is_ancestor(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        ancestor_sha: &str,
+        descendant_sha: &str,
+    ) -> anyhow::Result<bool>;
+
     /// Find the first PR of a commit
-    /// Returns the title and body of the PR
-    /// If no PR is found, returns empty strings
+    /// Returns the number, URL, title, body, labels and head branch of the PR,
+    /// as well as the user who merged it and the users who approved it
+    /// If no PR is found, returns empty values
     async fn find_first_pr_of_commit(
         &self,
         owner: &str,
         repo: &str,
         commit_sha: &str,
     ) -> anyhow::Result<FindFirstPrOfCommitResult>;
+
+    /// Get the state, labels and milestone of an issue
+    async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> anyhow::Result<GetIssueResult>;
+
+    /// Get the SHA of the commit a tag points to, peeling annotated tags
+    ///
+    /// Returns `None` if the tag does not exist
+    async fn get_tag_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>>;
+
+    /// Get the tag of the latest published release, `None` if there is no release
+    async fn get_latest_release_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> anyhow::Result<Option<String>>;
+
+    /// Get the web URL of the release of a tag, `None` if the tag has no release
+    async fn get_release_url(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>>;
+
+    /// Create an annotated tag on a commit
+    async fn create_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        commit_sha: &str,
+        message: &str,
+    ) -> anyhow::Result<()>;
+
+    /// Create a release of an existing tag
+    /// Returns the web URL of the release
+    async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        name: &str,
+        body: &str,
+    ) -> anyhow::Result<String>;
+
+    /// Get the name of the default branch of a repository
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> anyhow::Result<String>;
+
+    /// Create a branch pointing to the head of another branch
+    /// Keeps the branch if it already exists, e.g. after a promotion that failed to open its PR
+    async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        from_branch: &str,
+    ) -> anyhow::Result<()>;
+
+    /// Replace the contents of an existing file with a commit on a branch
+    async fn update_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        file_path: &str,
+        contents: &str,
+        message: &str,
+    ) -> anyhow::Result<()>;
+
+    /// Open a PR of a branch
+    /// Returns the web URL of the PR
+    async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> anyhow::Result<String>;
 }
 
 #[derive(Clone)]
@@ -109,9 +342,19 @@ pub struct GithubServiceImpl {
     pub token: String,
 }
 
+#[derive(Debug, Clone, Default)]
 pub struct FindFirstPrOfCommitResult {
-    pr_title: String,
-    pr_body: String,
+    pub pr_number: Option<u64>,
+    pub pr_url: String,
+    pub pr_title: String,
+    pub pr_body: String,
+    pub pr_labels: Vec<String>,
+    pub pr_merged_by: String,
+    pub pr_reviewers: Vec<String>,
+    /// Name of the branch the PR was opened from
+    pub pr_head_branch: String,
+    /// When the PR was merged, `None` if it is not merged or there is no PR
+    pub pr_merged_at: Option<DateTime<Utc>>,
 }
 
 impl GithubServiceImpl {
@@ -132,6 +375,116 @@ impl GithubServiceImpl {
             }
         }
     }
+
+    /// Send an authorized request and parse its JSON response
+    ///
+    /// Returns `None` if the resource is not found. `action` describes the request in errors.
+    async fn send_json(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> anyhow::Result<Option<serde_json::Value>> {
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "deppy")
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error {}: {} {}",
+                action,
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+
+        Ok(Some(serde_json::from_str(&response.text().await?)?))
+    }
+
+    /// Get the login of the user who merged the PR and the name of its head branch
+    /// If the PR is not merged, the login is an empty string
+    async fn get_pr_merged_by_and_head_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> anyhow::Result<(String, String)> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/repos/{}/{}/pulls/{}",
+                &self.base_url, owner, repo, pr_number
+            ))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error fetching PR #{}: {}",
+                pr_number,
+                response.status()
+            ));
+        }
+
+        let parsed_data: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+
+        Ok((
+            parsed_data["merged_by"]["login"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            parsed_data["head"]["ref"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+        ))
+    }
+
+    /// Get the logins of the users who approved the PR, in the order of their first approval
+    async fn get_pr_approvers(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+    ) -> anyhow::Result<Vec<String>> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/repos/{}/{}/pulls/{}/reviews",
+                &self.base_url, owner, repo, pr_number
+            ))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error fetching reviews of PR #{}: {}",
+                pr_number,
+                response.status()
+            ));
+        }
+
+        let parsed_data: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+
+        let mut approvers: Vec<String> = vec![];
+        for review in parsed_data.as_array().unwrap_or(&vec![]) {
+            if review["state"].as_str() != Some("APPROVED") {
+                continue;
+            }
+            if let Some(login) = review["user"]["login"].as_str() {
+                if !approvers.iter().any(|approver| approver == login) {
+                    approvers.push(login.to_string());
+                }
+            }
+        }
+
+        Ok(approvers)
+    }
 }
 
 #[async_trait]
@@ -146,10 +499,7 @@ impl GithubService for GithubServiceImpl {
         let response = reqwest::Client::new()
             .get(format!(
                 "{}/repos/{}/{}/commits/{}",
-                &self.base_url,
-                owner_name,
-                repo_name,
-                commit_sha.to_string()
+                &self.base_url, owner_name, repo_name, commit_sha
             ))
             .bearer_auth(&self.token)
             .send()
@@ -174,25 +524,32 @@ impl GithubService for GithubServiceImpl {
         let date_time: DateTime<Utc> = full_date_time.parse().expect("Failed to parse date-time");
 
         // TODO: execute in parallel with previous request
-        let (pr_title, pr_body) = if with_pr {
+        let pr_info = if with_pr {
             match self
-                .find_first_pr_of_commit(&owner_name, &repo_name, &commit_sha)
+                .find_first_pr_of_commit(owner_name, repo_name, commit_sha)
                 .await
             {
-                Ok(pr_info) => (pr_info.pr_title, pr_info.pr_body),
+                Ok(pr_info) => pr_info,
                 Err(_) => return Err(anyhow!("Error")),
             }
         } else {
-            ("".to_string(), "".to_string())
+            FindFirstPrOfCommitResult::default()
         };
 
         return Ok(GetCommitResult {
-            date_time: date_time.clone(),
+            date_time,
             author_email: author_email.to_string(),
             sha: commit_sha.to_string(),
             full_message: message.to_string(),
-            pr_title,
-            pr_body,
+            pr_title: pr_info.pr_title,
+            pr_body: pr_info.pr_body,
+            pr_number: pr_info.pr_number,
+            pr_url: pr_info.pr_url,
+            pr_labels: pr_info.pr_labels,
+            pr_merged_by: pr_info.pr_merged_by,
+            pr_reviewers: pr_info.pr_reviewers,
+            pr_head_branch: pr_info.pr_head_branch,
+            pr_merged_at: pr_info.pr_merged_at,
         });
     }
 
@@ -202,37 +559,274 @@ impl GithubService for GithubServiceImpl {
         repo_name: &str,
         date_time: DateTime<Utc>,
     ) -> anyhow::Result<GetCommitsSinceResult> {
-        let commits: Page<RepoCommit> = self
+        // The commits are listed from the newest, follow the pages until the `since` cutoff
+        let mut commit_shas: Vec<String> = vec![];
+        let mut commit_parents: HashMap<String, Vec<String>> = HashMap::new();
+        let mut page = 1;
+        loop {
+            let request = reqwest::Client::new()
+                .get(format!(
+                    "{}/repos/{}/{}/commits",
+                    self.base_url, owner_name, repo_name
+                ))
+                .query(&[
+                    ("since", date_time.to_rfc3339()),
+                    ("per_page", COMMITS_PER_PAGE.to_string()),
+                    ("page", page.to_string()),
+                ]);
+            let commits = self
+                .send_json(request, "listing commits")
+                .await?
+                .and_then(|body| body.as_array().cloned())
+                .ok_or_else(|| anyhow!("Repository {}/{} not found", owner_name, repo_name))?;
+
+            for commit in &commits {
+                let Some(sha) = commit["sha"].as_str() else {
+                    continue;
+                };
+                let parents = commit["parents"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|parent| parent["sha"].as_str().map(|sha| sha.to_string()))
+                    .collect();
+                commit_shas.push(sha.to_string());
+                commit_parents.insert(sha.to_string(), parents);
+            }
+
+            if commits.len() < COMMITS_PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(GetCommitsSinceResult {
+            commit_shas,
+            commit_parents,
+        })
+    }
+
+    async fn get_contents(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<String> {
+        let contents = self
             .gh
             .repos(owner_name, repo_name)
-            .list_commits()
-            .since(date_time)
+            .get_content()
+            .path(file_path)
             .send()
             .await?;
 
-        let commit_shas: Vec<String> = commits
-            .into_iter()
-            .map(|commit| commit.sha.clone())
-            .collect();
-
-        Ok(GetCommitsSinceResult { commit_shas })
+        return Ok(contents.items[0].decoded_content().unwrap());
     }
 
-    async fn get_contents(
+    async fn get_contents_at(
         &self,
         owner_name: &str,
         repo_name: &str,
         file_path: &str,
+        git_ref: &str,
     ) -> anyhow::Result<String> {
         let contents = self
             .gh
             .repos(owner_name, repo_name)
             .get_content()
             .path(file_path)
+            .r#ref(git_ref)
             .send()
             .await?;
 
-        return Ok(contents.items[0].decoded_content().unwrap());
+        contents
+            .items
+            .first()
+            .and_then(|item| item.decoded_content())
+            .ok_or(anyhow!("{} not found at {}", file_path, git_ref))
+    }
+
+    async fn get_file_history(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<Vec<GetCommitResult>> {
+        let action = format!(
+            "fetching the history of {} of {}/{}",
+            file_path, owner_name, repo_name
+        );
+        // The commits are listed from the newest, follow the pages to the first revision
+        let mut history: Vec<GetCommitResult> = vec![];
+        let mut page = 1;
+        loop {
+            let commits = self
+                .send_json(
+                    reqwest::Client::new()
+                        .get(format!(
+                            "{}/repos/{}/{}/commits",
+                            &self.base_url, owner_name, repo_name
+                        ))
+                        .query(&[
+                            ("path", file_path.to_string()),
+                            ("per_page", COMMITS_PER_PAGE.to_string()),
+                            ("page", page.to_string()),
+                        ]),
+                    &action,
+                )
+                .await?
+                .and_then(|body| body.as_array().cloned())
+                .ok_or(anyhow!("Error {}: repository not found", action))?;
+
+            for commit in &commits {
+                let date = commit["commit"]["committer"]["date"]
+                    .as_str()
+                    .ok_or(anyhow!("Error {}: commit without a date", action))?;
+                history.push(GetCommitResult {
+                    date_time: DateTime::parse_from_rfc3339(date)?.with_timezone(&Utc),
+                    author_email: commit["commit"]["author"]["email"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    sha: commit["sha"].as_str().unwrap_or_default().to_string(),
+                    full_message: commit["commit"]["message"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    ..Default::default()
+                });
+            }
+
+            if commits.len() < COMMITS_PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(history)
+    }
+
+    async fn search_commits(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let action = format!(
+            "searching the commits of {}/{} for {}",
+            owner_name, repo_name, text
+        );
+        let query = format!("\"{}\" repo:{}/{}", text, owner_name, repo_name);
+        let results = self
+            .send_json(
+                reqwest::Client::new()
+                    .get(format!("{}/search/commits", &self.base_url))
+                    .query(&[
+                        ("q", query.as_str()),
+                        ("sort", "committer-date"),
+                        ("order", "desc"),
+                        ("per_page", "100"),
+                    ]),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: repository not found", action))?;
+
+        Ok(results["items"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|item| item["sha"].as_str())
+            .map(|sha| sha.to_string())
+            .collect())
+    }
+
+    async fn search_pull_requests(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<SearchPullRequestResult>> {
+        // The search API cannot match branch names, walk the closed PRs instead
+        let mut pull_requests: Vec<SearchPullRequestResult> = vec![];
+        for page in 1..=SEARCHED_PULL_REQUESTS.div_ceil(COMMITS_PER_PAGE) {
+            let action = format!(
+                "listing the closed PRs of {}/{} (page {})",
+                owner_name, repo_name, page
+            );
+            let items = self
+                .send_json(
+                    reqwest::Client::new()
+                        .get(format!(
+                            "{}/repos/{}/{}/pulls",
+                            &self.base_url, owner_name, repo_name
+                        ))
+                        .query(&[
+                            ("state", "closed"),
+                            ("sort", "updated"),
+                            ("direction", "desc"),
+                            ("per_page", &COMMITS_PER_PAGE.to_string()),
+                            ("page", &page.to_string()),
+                        ]),
+                    &action,
+                )
+                .await?
+                .ok_or(anyhow!("Error {}: repository not found", action))?;
+            let items = items.as_array().cloned().unwrap_or_default();
+
+            pull_requests.extend(
+                items
+                    .iter()
+                    // Closed without merging
+                    .filter(|item| !item["merged_at"].is_null())
+                    .map(|item| SearchPullRequestResult {
+                        title: item["title"].as_str().unwrap_or("").to_string(),
+                        head_branch: item["head"]["ref"].as_str().unwrap_or("").to_string(),
+                        merge_commit_sha: item["merge_commit_sha"]
+                            .as_str()
+                            .unwrap_or("")
+                            .to_string(),
+                    })
+                    .filter(|pull_request| {
+                        !pull_request.merge_commit_sha.is_empty() && pull_request.mentions(text)
+                    }),
+            );
+
+            if items.len() < COMMITS_PER_PAGE {
+                break;
+            }
+        }
+
+        Ok(pull_requests)
+    }
+
+    async fn is_ancestor(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        ancestor_sha: &str,
+        descendant_sha: &str,
+    ) -> anyhow::Result<bool> {
+        let action = format!(
+            "comparing {} and {} of {}/{}",
+            ancestor_sha, descendant_sha, owner_name, repo_name
+        );
+        let comparison = self
+            .send_json(
+                reqwest::Client::new().get(format!(
+                    "{}/repos/{}/{}/compare/{}...{}",
+                    &self.base_url, owner_name, repo_name, ancestor_sha, descendant_sha
+                )),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: commit not found", action))?;
+
+        // `ahead` means the descendant has every commit of the ancestor plus some more
+        Ok(matches!(
+            comparison["status"].as_str(),
+            Some("ahead" | "identical")
+        ))
     }
 
     async fn find_first_pr_of_commit(
@@ -260,71 +854,496 @@ impl GithubService for GithubServiceImpl {
 
         // check if items exists and not empty
         if parsed_data["items"].is_null() || parsed_data["items"].as_array().unwrap().is_empty() {
-            return Ok(FindFirstPrOfCommitResult {
-                pr_title: "".to_string(),
-                pr_body: "".to_string(),
-            });
+            return Ok(FindFirstPrOfCommitResult::default());
         }
 
-        let pr_title = parsed_data["items"][0]["title"].as_str().unwrap_or("");
-        let pr_body = parsed_data["items"][0]["body"].as_str().unwrap_or("");
+        let item = &parsed_data["items"][0];
+        let pr_number = item["number"].as_u64();
+        let pr_url = item["html_url"].as_str().unwrap_or("");
+        let pr_title = item["title"].as_str().unwrap_or("");
+        let pr_body = item["body"].as_str().unwrap_or("");
+        let pr_merged_at = item["pull_request"]["merged_at"]
+            .as_str()
+            .and_then(|merged_at| merged_at.parse().ok());
+        let pr_labels: Vec<String> = item["labels"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|label| label["name"].as_str())
+                    .map(|label| label.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // The PR is found already, missing merge or review details must not fail the commit
+        let ((pr_merged_by, pr_head_branch), pr_reviewers) = match pr_number {
+            Some(number) => {
+                let (merged_by_and_head_branch, approvers) = futures::join!(
+                    self.get_pr_merged_by_and_head_branch(owner, repo, number),
+                    self.get_pr_approvers(owner, repo, number)
+                );
+                let merged_by_and_head_branch = merged_by_and_head_branch.unwrap_or_else(|err| {
+                    eprintln!("Error fetching PR #{} details: {:?}", number, err);
+                    Default::default()
+                });
+                let approvers = approvers.unwrap_or_else(|err| {
+                    eprintln!("Error fetching PR #{} approvers: {:?}", number, err);
+                    Default::default()
+                });
+                (merged_by_and_head_branch, approvers)
+            }
+            None => (("".to_string(), "".to_string()), vec![]),
+        };
 
         Ok(FindFirstPrOfCommitResult {
+            pr_number,
+            pr_url: pr_url.to_string(),
             pr_title: pr_title.to_string(),
             pr_body: pr_body.to_string(),
+            pr_labels,
+            pr_merged_by,
+            pr_reviewers,
+            pr_head_branch,
+            pr_merged_at,
         })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::api::github_service::{GithubService, GithubServiceImpl};
-    use octocrab::{Octocrab, OctocrabBuilder};
 
-    #[tokio::test]
-    async fn test_get_commit() {
-        let mut server = mockito::Server::new_async().await;
+    async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> anyhow::Result<GetIssueResult> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/repos/{}/{}/issues/{}",
+                &self.base_url, owner, repo, issue_number
+            ))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
 
-        let _m = server
-            .mock("GET", "/repos/jrumjantsev/config/commits/123")
-            .with_status(200)
-            .with_body(
-                r#"{
-                    "commit": {
-                        "committer": {
-                            "date": "2021-08-01T00:00:00Z"
-                        },
-                        "author": {
-                            "email": "test@test.com"
-                        },
-                        "message": "Test commit message"
-                    }
-                }"#,
-            )
-            .create_async()
-            .await;
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error fetching issue {}/{}#{}: {}",
+                owner,
+                repo,
+                issue_number,
+                response.status()
+            ));
+        }
 
-        let gh = GithubServiceImpl {
-            gh: Octocrab::default(),
-            base_url: server.url(),
-            token: "test".to_string(),
-        };
+        let issue: serde_json::Value = serde_json::from_str(&response.text().await?)?;
 
-        let result = gh
-            .get_commit("jrumjantsev", "config", "123", false)
-            .await
-            .unwrap();
-        assert_eq!(result.author_email, "test@test.com");
-        assert_eq!(result.full_message, "Test commit message");
-        assert_eq!(result.sha, "123");
+        Ok(GetIssueResult {
+            title: issue["title"].as_str().unwrap_or("").to_string(),
+            assignee: issue["assignee"]["login"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            state: issue["state"].as_str().unwrap_or("").to_string(),
+            labels: issue["labels"]
+                .as_array()
+                .unwrap_or(&vec![])
+                .iter()
+                .filter_map(|label| label["name"].as_str())
+                .map(|label| label.to_string())
+                .collect(),
+            milestone: issue["milestone"]["title"].as_str().map(|m| m.to_string()),
+            is_pull_request: !issue["pull_request"].is_null(),
+        })
     }
 
-    #[tokio::test]
-    #[ignore]
-    async fn test_get_contents() {
-        let mut server = mockito::Server::new_async().await;
-
-        let _m = server
+    async fn get_tag_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let client = reqwest::Client::new();
+        let action = format!("fetching tag {} of {}/{}", tag, owner, repo);
+        let tag_ref = self
+            .send_json(
+                client.get(format!(
+                    "{}/repos/{}/{}/git/ref/tags/{}",
+                    &self.base_url, owner, repo, tag
+                )),
+                &action,
+            )
+            .await?;
+        let tag_object = match tag_ref {
+            Some(tag_ref) => tag_ref["object"].clone(),
+            None => return Ok(None),
+        };
+
+        // Lightweight tags point to the commit, annotated tags to a tag object
+        if tag_object["type"].as_str() != Some("tag") {
+            return Ok(tag_object["sha"].as_str().map(|sha| sha.to_string()));
+        }
+        let annotated_tag = self
+            .send_json(
+                client.get(format!(
+                    "{}/repos/{}/{}/git/tags/{}",
+                    &self.base_url,
+                    owner,
+                    repo,
+                    tag_object["sha"].as_str().unwrap_or_default()
+                )),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: tag object not found", action))?;
+
+        Ok(annotated_tag["object"]["sha"]
+            .as_str()
+            .map(|sha| sha.to_string()))
+    }
+
+    async fn get_latest_release_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let release = self
+            .send_json(
+                reqwest::Client::new().get(format!(
+                    "{}/repos/{}/{}/releases/latest",
+                    &self.base_url, owner, repo
+                )),
+                &format!("fetching the latest release of {}/{}", owner, repo),
+            )
+            .await?;
+
+        Ok(release.and_then(|release| release["tag_name"].as_str().map(|tag| tag.to_string())))
+    }
+
+    async fn get_release_url(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let release = self
+            .send_json(
+                reqwest::Client::new().get(format!(
+                    "{}/repos/{}/{}/releases/tags/{}",
+                    &self.base_url, owner, repo, tag
+                )),
+                &format!("fetching release {} of {}/{}", tag, owner, repo),
+            )
+            .await?;
+
+        Ok(release.and_then(|release| release["html_url"].as_str().map(|url| url.to_string())))
+    }
+
+    async fn create_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        commit_sha: &str,
+        message: &str,
+    ) -> anyhow::Result<()> {
+        let client = reqwest::Client::new();
+        let action = format!("creating tag {} of {}/{}", tag, owner, repo);
+
+        // An annotated tag is a tag object plus a reference to it
+        let tag_object = self
+            .send_json(
+                client
+                    .post(format!(
+                        "{}/repos/{}/{}/git/tags",
+                        &self.base_url, owner, repo
+                    ))
+                    .json(&serde_json::json!({
+                        "tag": tag,
+                        "message": message,
+                        "object": commit_sha,
+                        "type": "commit",
+                    })),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: repository not found", action))?;
+        self.send_json(
+            client
+                .post(format!(
+                    "{}/repos/{}/{}/git/refs",
+                    &self.base_url, owner, repo
+                ))
+                .json(&serde_json::json!({
+                    "ref": format!("refs/tags/{}", tag),
+                    "sha": tag_object["sha"],
+                })),
+            &action,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        name: &str,
+        body: &str,
+    ) -> anyhow::Result<String> {
+        let action = format!("creating release {} of {}/{}", tag, owner, repo);
+        let release = self
+            .send_json(
+                reqwest::Client::new()
+                    .post(format!(
+                        "{}/repos/{}/{}/releases",
+                        &self.base_url, owner, repo
+                    ))
+                    .json(&serde_json::json!({
+                        "tag_name": tag,
+                        "name": name,
+                        "body": body,
+                    })),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: repository not found", action))?;
+
+        Ok(release["html_url"].as_str().unwrap_or_default().to_string())
+    }
+
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> anyhow::Result<String> {
+        let action = format!("fetching repository {}/{}", owner, repo);
+        let repository = self
+            .send_json(
+                reqwest::Client::new().get(format!("{}/repos/{}/{}", &self.base_url, owner, repo)),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: repository not found", action))?;
+
+        repository["default_branch"]
+            .as_str()
+            .map(|branch| branch.to_string())
+            .ok_or(anyhow!("Error {}: no default branch", action))
+    }
+
+    async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        from_branch: &str,
+    ) -> anyhow::Result<()> {
+        let client = reqwest::Client::new();
+        let action = format!("creating branch {} of {}/{}", branch, owner, repo);
+        let existing_ref = self
+            .send_json(
+                client.get(format!(
+                    "{}/repos/{}/{}/git/ref/heads/{}",
+                    &self.base_url, owner, repo, branch
+                )),
+                &action,
+            )
+            .await?;
+        if existing_ref.is_some() {
+            return Ok(());
+        }
+
+        let from_ref = self
+            .send_json(
+                client.get(format!(
+                    "{}/repos/{}/{}/git/ref/heads/{}",
+                    &self.base_url, owner, repo, from_branch
+                )),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!(
+                "Error {}: branch {} not found",
+                action,
+                from_branch
+            ))?;
+        self.send_json(
+            client
+                .post(format!(
+                    "{}/repos/{}/{}/git/refs",
+                    &self.base_url, owner, repo
+                ))
+                .json(&serde_json::json!({
+                    "ref": format!("refs/heads/{}", branch),
+                    "sha": from_ref["object"]["sha"],
+                })),
+            &action,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        file_path: &str,
+        contents: &str,
+        message: &str,
+    ) -> anyhow::Result<()> {
+        let client = reqwest::Client::new();
+        let action = format!("updating {} of {}/{}", file_path, owner, repo);
+        let url = format!(
+            "{}/repos/{}/{}/contents/{}",
+            &self.base_url, owner, repo, file_path
+        );
+
+        // The blob SHA of the current file guards against overwriting concurrent changes
+        let current_file = self
+            .send_json(client.get(&url).query(&[("ref", branch)]), &action)
+            .await?
+            .ok_or(anyhow!("Error {}: file not found", action))?;
+        self.send_json(
+            client.put(&url).json(&serde_json::json!({
+                "message": message,
+                "content": BASE64_STANDARD.encode(contents),
+                "sha": current_file["sha"],
+                "branch": branch,
+            })),
+            &action,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> anyhow::Result<String> {
+        let action = format!("opening a PR of {} in {}/{}", head, owner, repo);
+        let pull_request = self
+            .send_json(
+                reqwest::Client::new()
+                    .post(format!("{}/repos/{}/{}/pulls", &self.base_url, owner, repo))
+                    .json(&serde_json::json!({
+                        "title": title,
+                        "head": head,
+                        "base": base,
+                        "body": body,
+                    })),
+                &action,
+            )
+            .await?
+            .ok_or(anyhow!("Error {}: repository not found", action))?;
+
+        Ok(pull_request["html_url"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::github_service::{GetCommitsSinceResult, GithubService, GithubServiceImpl};
+    use octocrab::{Octocrab, OctocrabBuilder};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_get_commit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/jrumjantsev/config/commits/123")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "commit": {
+                        "committer": {
+                            "date": "2021-08-01T00:00:00Z"
+                        },
+                        "author": {
+                            "email": "test@test.com"
+                        },
+                        "message": "Test commit message"
+                    }
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let result = gh
+            .get_commit("jrumjantsev", "config", "123", false)
+            .await
+            .unwrap();
+        assert_eq!(result.author_email, "test@test.com");
+        assert_eq!(result.full_message, "Test commit message");
+        assert_eq!(result.sha, "123");
+    }
+
+    #[test]
+    fn test_first_parent_shas() {
+        // m2 merges b2 (branch: b1 -> b2), m1 merges a1
+        let parents = |shas: &[&str]| shas.iter().map(|sha| sha.to_string()).collect();
+        let result = GetCommitsSinceResult {
+            commit_shas: vec!["m2", "b2", "b1", "m1", "a1"]
+                .into_iter()
+                .map(|sha| sha.to_string())
+                .collect(),
+            commit_parents: HashMap::from([
+                ("m2".to_string(), parents(&["m1", "b2"])),
+                ("b2".to_string(), parents(&["b1"])),
+                ("b1".to_string(), parents(&["m0"])),
+                ("m1".to_string(), parents(&["m0", "a1"])),
+                ("a1".to_string(), parents(&["m0"])),
+            ]),
+        };
+
+        assert_eq!(result.first_parent_shas(), vec!["m2", "m1"]);
+    }
+
+    #[test]
+    fn test_shas_between() {
+        let result = GetCommitsSinceResult {
+            commit_shas: vec!["d4d4", "c3c3", "b2b2", "a1a1"]
+                .into_iter()
+                .map(|sha| sha.to_string())
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            result.shas_between("a1", "c3c3").unwrap(),
+            vec!["c3c3", "b2b2"]
+        );
+        assert_eq!(
+            result.shas_between("a1", "d4").unwrap(),
+            vec!["d4d4", "c3c3", "b2b2"]
+        );
+        assert!(result.shas_between("a1", "a1a1").unwrap().is_empty());
+        assert!(result.shas_between("a1", "e5").is_err());
+        assert!(result.shas_between("f6", "c3").is_err());
+        // `from_sha` newer than `to_sha`
+        assert!(result.shas_between("d4", "b2").is_err());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_get_contents() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
             .mock(
                 "GET",
                 "/repos/jrumjantsev/config/contents/apps/foo/config.json",
@@ -359,7 +1378,26 @@ mod tests {
 
         let _m = server.mock("GET", "/search/issues?q=SHA:123+repo:jrumjantsev/foo+type:pr&sort=created&order=asc")
             .with_status(200)
-            .with_body(r#"{"items": [{"title": "FOO-123: created something", "body": "FOO-123: created something detailed"}]}"#)
+            .with_body(r#"{"items": [{"number": 42, "html_url": "https://github.com/jrumjantsev/foo/pull/42", "title": "FOO-123: created something", "body": "FOO-123: created something detailed", "labels": [{"name": "feature-flagged"}], "pull_request": {"merged_at": "2026-10-01T10:00:00Z"}}]}"#)
+            .create_async()
+            .await;
+        let _m_pr = server
+            .mock("GET", "/repos/jrumjantsev/foo/pulls/42")
+            .with_status(200)
+            .with_body(r#"{"number": 42, "merged_by": {"login": "merger"}, "head": {"ref": "feature/FOO-123-login"}}"#)
+            .create_async()
+            .await;
+        let _m_reviews = server
+            .mock("GET", "/repos/jrumjantsev/foo/pulls/42/reviews")
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"user": {"login": "alice"}, "state": "COMMENTED"},
+                    {"user": {"login": "bob"}, "state": "APPROVED"},
+                    {"user": {"login": "carol"}, "state": "CHANGES_REQUESTED"},
+                    {"user": {"login": "bob"}, "state": "APPROVED"}
+                ]"#,
+            )
             .create_async()
             .await;
 
@@ -374,7 +1412,639 @@ mod tests {
             .await
             .unwrap();
 
+        assert_eq!(result.pr_number, Some(42));
+        assert_eq!(result.pr_url, "https://github.com/jrumjantsev/foo/pull/42");
         assert_eq!(result.pr_title, "FOO-123: created something");
         assert_eq!(result.pr_body, "FOO-123: created something detailed");
+        assert_eq!(result.pr_labels, vec!["feature-flagged"]);
+        assert_eq!(result.pr_merged_by, "merger");
+        assert_eq!(result.pr_head_branch, "feature/FOO-123-login");
+        assert_eq!(result.pr_reviewers, vec!["bob"]);
+        assert_eq!(
+            result.pr_merged_at,
+            Some("2026-10-01T10:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_first_pr_of_commit_with_failing_details() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server.mock("GET", "/search/issues?q=SHA:123+repo:jrumjantsev/foo+type:pr&sort=created&order=asc")
+            .with_status(200)
+            .with_body(r#"{"items": [{"number": 42, "html_url": "https://github.com/jrumjantsev/foo/pull/42", "title": "FOO-123: created something", "labels": []}]}"#)
+            .create_async()
+            .await;
+        let _m_pr = server
+            .mock("GET", "/repos/jrumjantsev/foo/pulls/42")
+            .with_status(502)
+            .create_async()
+            .await;
+        let _m_reviews = server
+            .mock("GET", "/repos/jrumjantsev/foo/pulls/42/reviews")
+            .with_status(200)
+            .with_body(r#"[{"user": {"login": "bob"}, "state": "APPROVED"}]"#)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let result = gh
+            .find_first_pr_of_commit("jrumjantsev", "foo", "123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.pr_number, Some(42));
+        assert_eq!(result.pr_title, "FOO-123: created something");
+        assert_eq!(result.pr_merged_by, "");
+        assert_eq!(result.pr_head_branch, "");
+        assert_eq!(result.pr_reviewers, vec!["bob"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_first_pr_of_commit_without_pr() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/search/issues?q=SHA:123+repo:jrumjantsev/foo+type:pr&sort=created&order=asc",
+            )
+            .with_status(200)
+            .with_body(r#"{"items": []}"#)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let result = gh
+            .find_first_pr_of_commit("jrumjantsev", "foo", "123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.pr_number, None);
+        assert_eq!(result.pr_title, "");
+        assert!(result.pr_labels.is_empty());
+        assert!(result.pr_reviewers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_issue() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/jrumjantsev/foo/issues/12")
+            .with_status(200)
+            .with_body(
+                r#"{"number": 12, "state": "closed", "labels": [{"name": "ready"}], "milestone": {"title": "v1"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let result = gh.get_issue("jrumjantsev", "foo", 12).await.unwrap();
+
+        assert_eq!(result.state, "closed");
+        assert_eq!(result.labels, vec!["ready"]);
+        assert_eq!(result.milestone, Some("v1".to_string()));
+        assert!(!result.is_pull_request);
+    }
+
+    #[tokio::test]
+    async fn test_get_tag_commit_sha() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_annotated = server
+            .mock("GET", "/repos/jrumjantsev/foo/git/ref/tags/v1.0.0")
+            .with_status(200)
+            .with_body(r#"{"ref": "refs/tags/v1.0.0", "object": {"type": "tag", "sha": "tag123"}}"#)
+            .create_async()
+            .await;
+        let _m_tag_object = server
+            .mock("GET", "/repos/jrumjantsev/foo/git/tags/tag123")
+            .with_status(200)
+            .with_body(r#"{"tag": "v1.0.0", "object": {"type": "commit", "sha": "commit123"}}"#)
+            .create_async()
+            .await;
+        let _m_lightweight = server
+            .mock("GET", "/repos/jrumjantsev/foo/git/ref/tags/v0.9.0")
+            .with_status(200)
+            .with_body(
+                r#"{"ref": "refs/tags/v0.9.0", "object": {"type": "commit", "sha": "commit090"}}"#,
+            )
+            .create_async()
+            .await;
+        let _m_missing = server
+            .mock("GET", "/repos/jrumjantsev/foo/git/ref/tags/v2.0.0")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        assert_eq!(
+            gh.get_tag_commit_sha("jrumjantsev", "foo", "v1.0.0")
+                .await
+                .unwrap(),
+            Some("commit123".to_string())
+        );
+        assert_eq!(
+            gh.get_tag_commit_sha("jrumjantsev", "foo", "v0.9.0")
+                .await
+                .unwrap(),
+            Some("commit090".to_string())
+        );
+        assert_eq!(
+            gh.get_tag_commit_sha("jrumjantsev", "foo", "v2.0.0")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_tag_and_release() {
+        let mut server = mockito::Server::new_async().await;
+
+        let m_tag_object = server
+            .mock("POST", "/repos/jrumjantsev/foo/git/tags")
+            .match_header("Authorization", "Bearer test")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "tag": "v1.1.0",
+                "object": "abc123",
+                "type": "commit"
+            })))
+            .with_status(201)
+            .with_body(r#"{"sha": "tag456"}"#)
+            .create_async()
+            .await;
+        let m_tag_ref = server
+            .mock("POST", "/repos/jrumjantsev/foo/git/refs")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "ref": "refs/tags/v1.1.0",
+                "sha": "tag456"
+            })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _m_no_release = server
+            .mock("GET", "/repos/jrumjantsev/foo/releases/tags/v1.1.0")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _m_release = server
+            .mock("POST", "/repos/jrumjantsev/foo/releases")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "tag_name": "v1.1.0",
+                "body": "notes"
+            })))
+            .with_status(201)
+            .with_body(r#"{"html_url": "https://github.com/jrumjantsev/foo/releases/tag/v1.1.0"}"#)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        assert_eq!(
+            gh.get_release_url("jrumjantsev", "foo", "v1.1.0")
+                .await
+                .unwrap(),
+            None
+        );
+        gh.create_tag("jrumjantsev", "foo", "v1.1.0", "abc123", "Release v1.1.0")
+            .await
+            .unwrap();
+        let release_url = gh
+            .create_release("jrumjantsev", "foo", "v1.1.0", "v1.1.0", "notes")
+            .await
+            .unwrap();
+
+        m_tag_object.assert_async().await;
+        m_tag_ref.assert_async().await;
+        assert_eq!(
+            release_url,
+            "https://github.com/jrumjantsev/foo/releases/tag/v1.1.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_pull_request_with_file_update() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_repo = server
+            .mock("GET", "/repos/jrumjantsev/config")
+            .with_status(200)
+            .with_body(r#"{"default_branch": "main"}"#)
+            .create_async()
+            .await;
+        let _m_main = server
+            .mock("GET", "/repos/jrumjantsev/config/git/ref/heads/main")
+            .with_status(200)
+            .with_body(
+                r#"{"ref": "refs/heads/main", "object": {"type": "commit", "sha": "main123"}}"#,
+            )
+            .create_async()
+            .await;
+        let _m_new_branch = server
+            .mock("GET", "/repos/jrumjantsev/config/git/ref/heads/promote-foo")
+            .with_status(404)
+            .create_async()
+            .await;
+        let m_branch = server
+            .mock("POST", "/repos/jrumjantsev/config/git/refs")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "ref": "refs/heads/promote-foo",
+                "sha": "main123"
+            })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _m_file = server
+            .mock(
+                "GET",
+                "/repos/jrumjantsev/config/contents/apps/foo/config.json",
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "ref".to_string(),
+                "promote-foo".to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"sha": "blob123"}"#)
+            .create_async()
+            .await;
+        let m_update = server
+            .mock(
+                "PUT",
+                "/repos/jrumjantsev/config/contents/apps/foo/config.json",
+            )
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "message": "Promote foo",
+                "content": "e30K",
+                "sha": "blob123",
+                "branch": "promote-foo"
+            })))
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _m_pull_request = server
+            .mock("POST", "/repos/jrumjantsev/config/pulls")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "head": "promote-foo",
+                "base": "main"
+            })))
+            .with_status(201)
+            .with_body(r#"{"html_url": "https://github.com/jrumjantsev/config/pull/7"}"#)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let base = gh
+            .get_default_branch("jrumjantsev", "config")
+            .await
+            .unwrap();
+        gh.create_branch("jrumjantsev", "config", "promote-foo", &base)
+            .await
+            .unwrap();
+        gh.update_file(
+            "jrumjantsev",
+            "config",
+            "promote-foo",
+            "apps/foo/config.json",
+            "{}\n",
+            "Promote foo",
+        )
+        .await
+        .unwrap();
+        let pull_request_url = gh
+            .create_pull_request(
+                "jrumjantsev",
+                "config",
+                "promote-foo",
+                &base,
+                "Promote foo",
+                "",
+            )
+            .await
+            .unwrap();
+
+        m_branch.assert_async().await;
+        m_update.assert_async().await;
+        assert_eq!(
+            pull_request_url,
+            "https://github.com/jrumjantsev/config/pull/7"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_branch_keeps_existing_branch() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_existing = server
+            .mock("GET", "/repos/jrumjantsev/config/git/ref/heads/promote-foo")
+            .with_status(200)
+            .with_body(
+                r#"{"ref": "refs/heads/promote-foo", "object": {"type": "commit", "sha": "foo123"}}"#,
+            )
+            .create_async()
+            .await;
+        let m_branch = server
+            .mock("POST", "/repos/jrumjantsev/config/git/refs")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        gh.create_branch("jrumjantsev", "config", "promote-foo", "main")
+            .await
+            .unwrap();
+
+        m_branch.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_file_history() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/repos/jrumjantsev/config/commits")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "path".to_string(),
+                    "apps/foo/config.json".to_string(),
+                ),
+                mockito::Matcher::UrlEncoded("per_page".to_string(), "100".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"sha": "def", "commit": {"message": "Promote foo to prod", "author": {"email": "me@example.com"}, "committer": {"date": "2026-10-02T10:00:00Z"}}},
+                    {"sha": "abc", "commit": {"message": "Add foo", "author": {"email": "you@example.com"}, "committer": {"date": "2026-10-01T10:00:00Z"}}}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let history = gh
+            .get_file_history("jrumjantsev", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].sha, "def");
+        assert_eq!(history[0].author_email, "me@example.com");
+        assert_eq!(history[0].full_message, "Promote foo to prod");
+        assert_eq!(
+            history[1].date_time.to_rfc3339(),
+            "2026-10-01T10:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_file_history_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let first_page: Vec<serde_json::Value> = (0..100)
+            .map(|index| {
+                serde_json::json!({
+                    "sha": format!("c{}", index),
+                    "commit": {"message": "Promote foo", "author": {"email": "me@example.com"}, "committer": {"date": "2026-10-02T10:00:00Z"}}
+                })
+            })
+            .collect();
+        let _m_first = server
+            .mock("GET", "/repos/jrumjantsev/config/commits")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "path".to_string(),
+                    "apps/foo/config.json".to_string(),
+                ),
+                mockito::Matcher::UrlEncoded("page".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(serde_json::Value::Array(first_page).to_string())
+            .create_async()
+            .await;
+        let _m_second = server
+            .mock("GET", "/repos/jrumjantsev/config/commits")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "page".to_string(),
+                "2".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"sha": "first", "commit": {"message": "Add foo", "author": {"email": "you@example.com"}, "committer": {"date": "2026-10-01T10:00:00Z"}}}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let history = gh
+            .get_file_history("jrumjantsev", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        assert_eq!(history.len(), 101);
+        assert_eq!(history[100].sha, "first");
+    }
+
+    #[tokio::test]
+    async fn test_get_commits_since_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let first_page: Vec<serde_json::Value> = (0..100)
+            .map(|index| {
+                serde_json::json!({
+                    "sha": format!("n{}", index),
+                    "parents": [{"sha": format!("n{}", index + 1)}]
+                })
+            })
+            .collect();
+        let _m_first = server
+            .mock("GET", "/repos/jrumjantsev/foo/commits")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "since".to_string(),
+                    "2026-10-01T10:00:00+00:00".to_string(),
+                ),
+                mockito::Matcher::UrlEncoded("per_page".to_string(), "100".to_string()),
+                mockito::Matcher::UrlEncoded("page".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(serde_json::Value::Array(first_page).to_string())
+            .create_async()
+            .await;
+        let _m_second = server
+            .mock("GET", "/repos/jrumjantsev/foo/commits")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "page".to_string(),
+                "2".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"sha": "n100", "parents": [{"sha": "deployed"}]},
+                    {"sha": "deployed", "parents": [{"sha": "older"}]}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let result = gh
+            .get_commits_since(
+                "jrumjantsev",
+                "foo",
+                "2026-10-01T10:00:00Z".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.commit_shas.len(), 102);
+        assert_eq!(result.commit_parents["n100"], vec!["deployed"]);
+        assert_eq!(result.shas_between("deployed", "n0").unwrap().len(), 101);
+    }
+
+    #[tokio::test]
+    async fn test_search_commits_and_is_ancestor() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_search = server
+            .mock("GET", "/search/commits")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".to_string(),
+                "\"FOO-123\" repo:jrumjantsev/foo".to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"total_count": 2, "items": [{"sha": "def"}, {"sha": "abc"}]}"#)
+            .create_async()
+            .await;
+        let _m_ahead = server
+            .mock("GET", "/repos/jrumjantsev/foo/compare/abc...def")
+            .with_status(200)
+            .with_body(r#"{"status": "ahead", "ahead_by": 1, "behind_by": 0}"#)
+            .create_async()
+            .await;
+        let _m_behind = server
+            .mock("GET", "/repos/jrumjantsev/foo/compare/def...abc")
+            .with_status(200)
+            .with_body(r#"{"status": "behind", "ahead_by": 0, "behind_by": 1}"#)
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        assert_eq!(
+            gh.search_commits("jrumjantsev", "foo", "FOO-123")
+                .await
+                .unwrap(),
+            vec!["def".to_string(), "abc".to_string()]
+        );
+        assert!(gh
+            .is_ancestor("jrumjantsev", "foo", "abc", "def")
+            .await
+            .unwrap());
+        assert!(!gh
+            .is_ancestor("jrumjantsev", "foo", "def", "abc")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_search_pull_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_pulls = server
+            .mock("GET", "/repos/jrumjantsev/foo/pulls")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".to_string(), "closed".to_string()),
+                mockito::Matcher::UrlEncoded("page".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"title": "Add login", "head": {"ref": "feature/foo-123-login"}, "merged_at": "2026-10-02T10:00:00Z", "merge_commit_sha": "aaa"},
+                    {"title": "FOO-123 follow-up", "head": {"ref": "follow-up"}, "merged_at": "2026-10-01T10:00:00Z", "merge_commit_sha": "bbb"},
+                    {"title": "FOO-123 abandoned", "head": {"ref": "abandoned"}, "merged_at": null, "merge_commit_sha": "ccc"},
+                    {"title": "Unrelated", "head": {"ref": "unrelated"}, "merged_at": "2026-09-30T10:00:00Z", "merge_commit_sha": "ddd"}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let gh = GithubServiceImpl {
+            gh: Octocrab::default(),
+            base_url: server.url(),
+            token: "test".to_string(),
+        };
+
+        let pull_requests = gh
+            .search_pull_requests("jrumjantsev", "foo", "FOO-123")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pull_requests
+                .iter()
+                .map(|pull_request| pull_request.merge_commit_sha.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["aaa", "bbb"]
+        );
+        assert_eq!(pull_requests[0].head_branch, "feature/foo-123-login");
     }
 }
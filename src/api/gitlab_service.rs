@@ -0,0 +1,922 @@
+use crate::api::errors::{GitLabBaseUrlUndefined, GitLabTokenUndefined};
+use crate::api::github_service::{
+    FindFirstPrOfCommitResult, GetCommitResult, GetCommitsSinceResult, GetIssueResult,
+    GithubService, SearchPullRequestResult,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Number of pages of 100 merge requests searched by `search_pull_requests`
+const SEARCHED_MERGE_REQUEST_PAGES: usize = 5;
+
+/// `GithubService` backed by the GitLab REST API (v4)
+///
+/// `owner_name/repo_name` is used as the GitLab project path, merge requests take the place of PRs.
+#[derive(Clone)]
+pub struct GitlabServiceImpl {
+    pub base_url: String,
+    pub token: String,
+}
+
+impl GitlabServiceImpl {
+    /// `base_url` is the GitLab server, e.g. `https://gitlab.com`
+    pub fn new(base_url: Option<&str>, token: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        match (base_url, token) {
+            (None, _) => Err(Box::new(GitLabBaseUrlUndefined)),
+            (_, None) => Err(Box::new(GitLabTokenUndefined)),
+            (Some(base_url), Some(token)) => Ok(Self {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                token: token.to_string(),
+            }),
+        }
+    }
+
+    fn project_url(&self, owner_name: &str, repo_name: &str) -> String {
+        format!(
+            "{}/api/v4/projects/{}",
+            self.base_url,
+            urlencoding::encode(&format!("{}/{}", owner_name, repo_name))
+        )
+    }
+
+    async fn get_json(&self, url: &str) -> anyhow::Result<serde_json::Value> {
+        let response = reqwest::Client::new()
+            .get(url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!("Error fetching {}: {}", url, response.status()));
+        }
+
+        let body = response.text().await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Items of every page of a list endpoint, following the `X-Next-Page` header
+    async fn get_json_pages(&self, url: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        self.get_json_pages_up_to(url, usize::MAX).await
+    }
+
+    /// Items of the first `max_pages` pages of a list endpoint
+    async fn get_json_pages_up_to(
+        &self,
+        url: &str,
+        max_pages: usize,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let mut items: Vec<serde_json::Value> = vec![];
+        let mut page = "1".to_string();
+        for _ in 0..max_pages {
+            let page_url = format!("{}&page={}", url, page);
+            let response = reqwest::Client::new()
+                .get(&page_url)
+                .header("PRIVATE-TOKEN", &self.token)
+                .send()
+                .await?;
+
+            if !(response.status().is_success()) {
+                return Err(anyhow!(
+                    "Error fetching {}: {}",
+                    page_url,
+                    response.status()
+                ));
+            }
+
+            let next_page = response
+                .headers()
+                .get("X-Next-Page")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("")
+                .to_string();
+            let body: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+            items.extend(body.as_array().cloned().unwrap_or_default());
+
+            if next_page.is_empty() {
+                break;
+            }
+            page = next_page;
+        }
+
+        Ok(items)
+    }
+
+    /// Get the usernames of the users who approved the merge request
+    async fn get_mr_approvers(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        mr_iid: u64,
+    ) -> anyhow::Result<Vec<String>> {
+        let approvals = self
+            .get_json(&format!(
+                "{}/merge_requests/{}/approvals",
+                self.project_url(owner_name, repo_name),
+                mr_iid
+            ))
+            .await?;
+
+        Ok(approvals["approved_by"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|approval| approval["user"]["username"].as_str())
+            .map(|username| username.to_string())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl GithubService for GitlabServiceImpl {
+    async fn get_commit(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        commit_sha: &str,
+        with_pr: bool,
+    ) -> anyhow::Result<GetCommitResult> {
+        let commit = self
+            .get_json(&format!(
+                "{}/repository/commits/{}",
+                self.project_url(owner_name, repo_name),
+                commit_sha
+            ))
+            .await?;
+
+        let date_time: DateTime<Utc> = DateTime::parse_from_rfc3339(
+            commit["committed_date"]
+                .as_str()
+                .ok_or(anyhow!("Commit {} has no date", commit_sha))?,
+        )?
+        .with_timezone(&Utc);
+        let author_email = commit["author_email"].as_str().unwrap_or("");
+        let message = commit["message"].as_str().unwrap_or("");
+
+        let pr_info = if with_pr {
+            self.find_first_pr_of_commit(owner_name, repo_name, commit_sha)
+                .await?
+        } else {
+            FindFirstPrOfCommitResult::default()
+        };
+
+        Ok(GetCommitResult {
+            date_time,
+            author_email: author_email.to_string(),
+            sha: commit_sha.to_string(),
+            full_message: message.to_string(),
+            pr_title: pr_info.pr_title,
+            pr_body: pr_info.pr_body,
+            pr_number: pr_info.pr_number,
+            pr_url: pr_info.pr_url,
+            pr_labels: pr_info.pr_labels,
+            pr_merged_by: pr_info.pr_merged_by,
+            pr_reviewers: pr_info.pr_reviewers,
+            pr_head_branch: pr_info.pr_head_branch,
+            pr_merged_at: pr_info.pr_merged_at,
+        })
+    }
+
+    async fn get_commits_since(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        date_time: DateTime<Utc>,
+    ) -> anyhow::Result<GetCommitsSinceResult> {
+        let commits = self
+            .get_json_pages(&format!(
+                "{}/repository/commits?since={}&per_page=100",
+                self.project_url(owner_name, repo_name),
+                urlencoding::encode(&date_time.to_rfc3339())
+            ))
+            .await?;
+
+        let mut commit_shas: Vec<String> = vec![];
+        let mut commit_parents: HashMap<String, Vec<String>> = HashMap::new();
+        for commit in &commits {
+            let sha = match commit["id"].as_str() {
+                Some(sha) => sha.to_string(),
+                None => continue,
+            };
+            let parents = commit["parent_ids"]
+                .as_array()
+                .unwrap_or(&vec![])
+                .iter()
+                .filter_map(|parent| parent.as_str())
+                .map(|parent| parent.to_string())
+                .collect();
+            commit_shas.push(sha.clone());
+            commit_parents.insert(sha, parents);
+        }
+
+        Ok(GetCommitsSinceResult {
+            commit_shas,
+            commit_parents,
+        })
+    }
+
+    async fn get_contents(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<String> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/repository/files/{}/raw?ref=HEAD",
+                self.project_url(owner_name, repo_name),
+                urlencoding::encode(file_path)
+            ))
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error fetching {}: {}",
+                file_path,
+                response.status()
+            ));
+        }
+
+        Ok(response.text().await?)
+    }
+
+    async fn get_contents_at(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+        git_ref: &str,
+    ) -> anyhow::Result<String> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/repository/files/{}/raw?ref={}",
+                self.project_url(owner_name, repo_name),
+                urlencoding::encode(file_path),
+                urlencoding::encode(git_ref)
+            ))
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await?;
+
+        if !(response.status().is_success()) {
+            return Err(anyhow!(
+                "Error fetching {} at {}: {}",
+                file_path,
+                git_ref,
+                response.status()
+            ));
+        }
+
+        Ok(response.text().await?)
+    }
+
+    async fn get_file_history(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<Vec<GetCommitResult>> {
+        let commits = self
+            .get_json_pages(&format!(
+                "{}/repository/commits?path={}&per_page=100",
+                self.project_url(owner_name, repo_name),
+                urlencoding::encode(file_path)
+            ))
+            .await?;
+
+        commits
+            .iter()
+            .map(|commit| {
+                let sha = commit["id"].as_str().unwrap_or("");
+                let date_time: DateTime<Utc> = DateTime::parse_from_rfc3339(
+                    commit["committed_date"]
+                        .as_str()
+                        .ok_or(anyhow!("Commit {} has no date", sha))?,
+                )?
+                .with_timezone(&Utc);
+                Ok(GetCommitResult {
+                    date_time,
+                    author_email: commit["author_email"].as_str().unwrap_or("").to_string(),
+                    sha: sha.to_string(),
+                    full_message: commit["message"].as_str().unwrap_or("").to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    async fn search_commits(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let commits = self
+            .get_json_pages(&format!(
+                "{}/search?scope=commits&search={}&per_page=100",
+                self.project_url(owner_name, repo_name),
+                urlencoding::encode(text)
+            ))
+            .await?;
+
+        Ok(commits
+            .iter()
+            .filter_map(|commit| commit["id"].as_str())
+            .map(|sha| sha.to_string())
+            .collect())
+    }
+
+    async fn search_pull_requests(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<SearchPullRequestResult>> {
+        // `search` matches titles and descriptions but not branches, filter the merge requests here
+        let merge_requests = self
+            .get_json_pages_up_to(
+                &format!(
+                    "{}/merge_requests?state=merged&order_by=updated_at&sort=desc&per_page=100",
+                    self.project_url(owner_name, repo_name)
+                ),
+                SEARCHED_MERGE_REQUEST_PAGES,
+            )
+            .await?;
+
+        Ok(merge_requests
+            .iter()
+            .map(|merge_request| SearchPullRequestResult {
+                title: merge_request["title"].as_str().unwrap_or("").to_string(),
+                head_branch: merge_request["source_branch"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string(),
+                // Fast-forward merges of squashed merge requests have no merge commit
+                merge_commit_sha: merge_request["merge_commit_sha"]
+                    .as_str()
+                    .or(merge_request["squash_commit_sha"].as_str())
+                    .unwrap_or("")
+                    .to_string(),
+            })
+            .filter(|merge_request| {
+                !merge_request.merge_commit_sha.is_empty() && merge_request.mentions(text)
+            })
+            .collect())
+    }
+
+    async fn is_ancestor(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        ancestor_sha: &str,
+        descendant_sha: &str,
+    ) -> anyhow::Result<bool> {
+        let merge_base = self
+            .get_json(&format!(
+                "{}/repository/merge_base?refs[]={}&refs[]={}",
+                self.project_url(owner_name, repo_name),
+                ancestor_sha,
+                descendant_sha
+            ))
+            .await?;
+        let merge_base_sha = merge_base["id"].as_str().unwrap_or("");
+
+        // Either SHA may be abbreviated
+        Ok(!merge_base_sha.is_empty()
+            && (merge_base_sha.starts_with(ancestor_sha)
+                || ancestor_sha.starts_with(merge_base_sha)))
+    }
+
+    async fn find_first_pr_of_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+    ) -> anyhow::Result<FindFirstPrOfCommitResult> {
+        let merge_requests = self
+            .get_json(&format!(
+                "{}/repository/commits/{}/merge_requests",
+                self.project_url(owner, repo),
+                commit_sha
+            ))
+            .await?;
+
+        let merge_request = match merge_requests.as_array().and_then(|merge_requests| {
+            merge_requests
+                .iter()
+                .min_by_key(|mr| mr["created_at"].as_str().unwrap_or("").to_string())
+        }) {
+            Some(merge_request) => merge_request,
+            None => return Ok(FindFirstPrOfCommitResult::default()),
+        };
+
+        let pr_number = merge_request["iid"].as_u64();
+        let pr_labels: Vec<String> = merge_request["labels"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|label| label.as_str())
+            .map(|label| label.to_string())
+            .collect();
+        let pr_merged_by = merge_request["merge_user"]["username"]
+            .as_str()
+            .or(merge_request["merged_by"]["username"].as_str())
+            .unwrap_or("");
+        let pr_reviewers = match pr_number {
+            Some(iid) => self.get_mr_approvers(owner, repo, iid).await?,
+            None => vec![],
+        };
+
+        Ok(FindFirstPrOfCommitResult {
+            pr_number,
+            pr_url: merge_request["web_url"].as_str().unwrap_or("").to_string(),
+            pr_title: merge_request["title"].as_str().unwrap_or("").to_string(),
+            pr_body: merge_request["description"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            pr_labels,
+            pr_merged_by: pr_merged_by.to_string(),
+            pr_reviewers,
+            pr_head_branch: merge_request["source_branch"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            pr_merged_at: merge_request["merged_at"]
+                .as_str()
+                .and_then(|merged_at| merged_at.parse().ok()),
+        })
+    }
+
+    async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> anyhow::Result<GetIssueResult> {
+        let issue = self
+            .get_json(&format!(
+                "{}/issues/{}",
+                self.project_url(owner, repo),
+                issue_number
+            ))
+            .await?;
+
+        Ok(GetIssueResult {
+            title: issue["title"].as_str().unwrap_or("").to_string(),
+            assignee: issue["assignees"][0]["username"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            // GitLab calls open issues `opened`
+            state: match issue["state"].as_str().unwrap_or("") {
+                "opened" => "open".to_string(),
+                state => state.to_string(),
+            },
+            labels: issue["labels"]
+                .as_array()
+                .unwrap_or(&vec![])
+                .iter()
+                .filter_map(|label| label.as_str())
+                .map(|label| label.to_string())
+                .collect(),
+            milestone: issue["milestone"]["title"].as_str().map(|m| m.to_string()),
+            is_pull_request: false,
+        })
+    }
+
+    async fn get_tag_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch tag {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_latest_release_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch the latest release of {}/{}",
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_release_url(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot fetch release {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        _commit_sha: &str,
+        _message: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot create tag {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        _name: &str,
+        _body: &str,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Releases are only supported on GitHub, cannot create release {} of {}/{}",
+            tag,
+            owner,
+            repo
+        ))
+    }
+
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot fetch the default branch of {}/{}",
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        _from_branch: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot create branch {} of {}/{}",
+            branch,
+            owner,
+            repo
+        ))
+    }
+
+    async fn update_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        _branch: &str,
+        file_path: &str,
+        _contents: &str,
+        _message: &str,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot update {} of {}/{}",
+            file_path,
+            owner,
+            repo
+        ))
+    }
+
+    async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        _base: &str,
+        _title: &str,
+        _body: &str,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "Promotions are only supported on GitHub, cannot open a PR of {} in {}/{}",
+            head,
+            owner,
+            repo
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_commit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/repository/commits/123",
+            )
+            .match_header("PRIVATE-TOKEN", "test")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "id": "123",
+                    "committed_date": "2021-08-01T02:00:00.000+02:00",
+                    "author_email": "test@test.com",
+                    "message": "FOO-1: Test commit message"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .get_commit("jrumjantsev", "foo", "123", false)
+            .await
+            .unwrap();
+
+        assert_eq!(result.sha, "123");
+        assert_eq!(result.author_email, "test@test.com");
+        assert_eq!(result.full_message, "FOO-1: Test commit message");
+        assert_eq!(
+            result.date_time,
+            "2021-08-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_commits_since() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/repository/commits",
+            )
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("since".into(), "2021-08-01T00:00:00+00:00".into()),
+                mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("X-Next-Page", "2")
+            .with_body(r#"[{"id": "bbb", "parent_ids": ["aaa", "zzz"]}]"#)
+            .create_async()
+            .await;
+        let _m_second_page = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/repository/commits",
+            )
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_status(200)
+            .with_header("X-Next-Page", "")
+            .with_body(r#"[{"id": "aaa", "parent_ids": []}]"#)
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .get_commits_since(
+                "jrumjantsev",
+                "foo",
+                "2021-08-01T00:00:00Z".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.commit_shas, vec!["bbb", "aaa"]);
+        assert_eq!(result.commit_parents["bbb"], vec!["aaa", "zzz"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_file_history_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_first = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Fconfig/repository/commits",
+            )
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("path".into(), "apps/foo/config.json".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("X-Next-Page", "2")
+            .with_body(
+                r#"[{"id": "bbb", "committed_date": "2021-08-02T00:00:00Z", "author_email": "me@example.com", "message": "Promote foo"}]"#,
+            )
+            .create_async()
+            .await;
+        let _m_second = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Fconfig/repository/commits",
+            )
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_status(200)
+            .with_header("X-Next-Page", "")
+            .with_body(
+                r#"[{"id": "aaa", "committed_date": "2021-08-01T00:00:00Z", "author_email": "you@example.com", "message": "Add foo"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let history = gitlab
+            .get_file_history("jrumjantsev", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        let shas: Vec<&str> = history.iter().map(|commit| commit.sha.as_str()).collect();
+        assert_eq!(shas, vec!["bbb", "aaa"]);
+        assert_eq!(history[1].full_message, "Add foo");
+    }
+
+    #[tokio::test]
+    async fn test_search_commits_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m_first_page = server
+            .mock("GET", "/api/v4/projects/jrumjantsev%2Ffoo/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("scope".into(), "commits".into()),
+                mockito::Matcher::UrlEncoded("search".into(), "FOO-123".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("X-Next-Page", "2")
+            .with_body(r#"[{"id": "ccc"}]"#)
+            .create_async()
+            .await;
+        let _m_second_page = server
+            .mock("GET", "/api/v4/projects/jrumjantsev%2Ffoo/search")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_status(200)
+            .with_body(r#"[{"id": "aaa"}]"#)
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .search_commits("jrumjantsev", "foo", "FOO-123")
+            .await
+            .unwrap();
+
+        assert_eq!(result, vec!["ccc", "aaa"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_pull_requests() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("GET", "/api/v4/projects/jrumjantsev%2Ffoo/merge_requests")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "merged".into()),
+                mockito::Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"title": "Add login", "source_branch": "FOO-123-login", "merge_commit_sha": "aaa", "squash_commit_sha": null},
+                    {"title": "FOO-123: follow-up", "source_branch": "follow-up", "merge_commit_sha": null, "squash_commit_sha": "bbb"},
+                    {"title": "Unrelated", "source_branch": "unrelated", "merge_commit_sha": "ccc", "squash_commit_sha": null}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .search_pull_requests("jrumjantsev", "foo", "foo-123")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|merge_request| merge_request.merge_commit_sha.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["aaa", "bbb"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_contents() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Fconfig/repository/files/apps%2Ffoo%2Fconfig.json/raw?ref=HEAD",
+            )
+            .with_status(200)
+            .with_body(r#"{"identify": "foo123"}"#)
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .get_contents("jrumjantsev", "config", "apps/foo/config.json")
+            .await
+            .unwrap();
+
+        assert_eq!(result, r#"{"identify": "foo123"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_find_first_pr_of_commit() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/repository/commits/123/merge_requests",
+            )
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"iid": 8, "created_at": "2021-08-02T00:00:00Z", "title": "Later MR"},
+                    {
+                        "iid": 7,
+                        "created_at": "2021-08-01T00:00:00Z",
+                        "web_url": "https://gitlab.com/jrumjantsev/foo/-/merge_requests/7",
+                        "title": "FOO-123: created something",
+                        "description": "FOO-123: created something detailed",
+                        "labels": ["needs-migration"],
+                        "merge_user": {"username": "merger"}
+                    }
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let _m_approvals = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/merge_requests/7/approvals",
+            )
+            .with_status(200)
+            .with_body(r#"{"approved_by": [{"user": {"username": "bob"}}]}"#)
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .find_first_pr_of_commit("jrumjantsev", "foo", "123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.pr_number, Some(7));
+        assert_eq!(
+            result.pr_url,
+            "https://gitlab.com/jrumjantsev/foo/-/merge_requests/7"
+        );
+        assert_eq!(result.pr_title, "FOO-123: created something");
+        assert_eq!(result.pr_body, "FOO-123: created something detailed");
+        assert_eq!(result.pr_labels, vec!["needs-migration"]);
+        assert_eq!(result.pr_merged_by, "merger");
+        assert_eq!(result.pr_reviewers, vec!["bob"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_first_pr_of_commit_without_mr() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock(
+                "GET",
+                "/api/v4/projects/jrumjantsev%2Ffoo/repository/commits/123/merge_requests",
+            )
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let gitlab = GitlabServiceImpl::new(Some(&server.url()), Some("test")).unwrap();
+        let result = gitlab
+            .find_first_pr_of_commit("jrumjantsev", "foo", "123")
+            .await
+            .unwrap();
+
+        assert_eq!(result.pr_number, None);
+        assert_eq!(result.pr_title, "");
+    }
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Issue as seen by deppy, regardless of the issue tracker it comes from
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IssueInfo {
+    pub key: String,
+    pub summary: String,
+    pub status: String,
+    /// Display name of the assignee, empty if unassigned
+    pub assignee: String,
+    pub reporter: String,
+    /// e.g. `Bug` or `Story`
+    pub issue_type: String,
+    /// e.g. `Blocker` or `Major`
+    pub priority: String,
+    pub fix_versions: Vec<String>,
+    pub labels: Vec<String>,
+    pub components: Vec<String>,
+    /// Key of the epic the issue belongs to, empty if none
+    pub epic: String,
+    /// Additional fields requested from the tracker, rendered as text
+    pub extra_fields: HashMap<String, String>,
+    /// Whether the issue is ready to be deployed, according to the tracker's readiness rules
+    pub ready: bool,
+}
+
+#[async_trait]
+pub trait IssueTrackerService: Sync + Send {
+    /// Get the issues with the given keys
+    ///
+    /// Keys that do not exist in the tracker are left out of the result
+    async fn get_issues(&self, issue_keys: Vec<String>) -> Result<Vec<IssueInfo>, String>;
+
+    /// Move the issue to the given status, e.g. `Released` after a deployment
+    ///
+    /// Fails if the issue has no transition leading to the status.
+    async fn transition_issue(&self, issue_key: &str, status: &str) -> Result<(), String> {
+        Err(format!(
+            "Cannot move {} to {}: the issue tracker does not support transitions",
+            issue_key, status
+        ))
+    }
+
+    /// Comment on the issue, unless one of its comments already contains `marker`
+    ///
+    /// Returns whether the comment was added.
+    async fn add_comment_once(
+        &self,
+        issue_key: &str,
+        _comment: &str,
+        _marker: &str,
+    ) -> Result<bool, String> {
+        Err(format!(
+            "Cannot comment on {}: the issue tracker does not support comments",
+            issue_key
+        ))
+    }
+
+    /// Add the issue to a fix version, creating the version in the issue's project if needed
+    async fn add_fix_version(&self, issue_key: &str, version: &str) -> Result<(), String> {
+        Err(format!(
+            "Cannot add {} to {}: the issue tracker does not support fix versions",
+            issue_key, version
+        ))
+    }
+}
@@ -0,0 +1,246 @@
+use crate::api::errors::LinearTokenUndefined;
+use crate::api::issue_tracker_service::{IssueInfo, IssueTrackerService};
+use async_trait::async_trait;
+use serde_json::json;
+use std::error::Error;
+
+/// Default Linear API server
+const DEFAULT_SERVER: &str = "https://api.linear.app";
+
+/// Maximum number of issues fetched per request, Linear pages are capped at 250
+const PAGE_SIZE: usize = 100;
+
+const ISSUES_QUERY: &str = "query Issues($filter: IssueFilter!, $first: Int!) { issues(filter: $filter, first: $first) { nodes { identifier title priorityLabel state { name type } assignee { name } creator { name } labels { nodes { name } } } } }";
+
+/// `IssueTrackerService` backed by the Linear GraphQL API
+pub struct LinearServiceImpl {
+    pub base_url: String,
+    pub token: String,
+    /// Workflow state names that count as ready, when empty the states of type `completed` are ready
+    pub ready_states: Vec<String>,
+}
+
+impl LinearServiceImpl {
+    pub fn new(
+        base_url: Option<&str>,
+        token: Option<&str>,
+        ready_states: Vec<String>,
+    ) -> Result<Self, Box<dyn Error>> {
+        match token {
+            None => Err(Box::new(LinearTokenUndefined)),
+            Some(token) => Ok(Self {
+                base_url: base_url
+                    .unwrap_or(DEFAULT_SERVER)
+                    .trim_end_matches('/')
+                    .to_string(),
+                token: token.to_string(),
+                ready_states,
+            }),
+        }
+    }
+
+    /// Build a filter matching the issues by team key and number, `ENG-123` is team `ENG` and
+    /// number 123
+    ///
+    /// Linear cannot filter by identifier, and `issue(id:)` fails the whole query on an unknown
+    /// key, so unknown keys must simply match nothing. Keys that are not `TEAM-123` are skipped,
+    /// `None` if no key is left since an empty `or` matches every issue.
+    fn build_filter(issue_keys: &[String]) -> Option<serde_json::Value> {
+        let conditions: Vec<serde_json::Value> = issue_keys
+            .iter()
+            .filter_map(|key| {
+                let (team, number) = key.rsplit_once('-')?;
+                let number: u64 = number.parse().ok()?;
+                Some(json!({
+                    "team": { "key": { "eqIgnoreCase": team } },
+                    "number": { "eq": number }
+                }))
+            })
+            .collect();
+
+        if conditions.is_empty() {
+            return None;
+        }
+
+        Some(json!({ "or": conditions }))
+    }
+
+    fn is_ready(&self, state: &serde_json::Value) -> bool {
+        if self.ready_states.is_empty() {
+            return state["type"].as_str() == Some("completed");
+        }
+
+        let name = state["name"].as_str().unwrap_or_default();
+        self.ready_states
+            .iter()
+            .any(|ready_state| ready_state.eq_ignore_ascii_case(name))
+    }
+}
+
+#[async_trait]
+impl IssueTrackerService for LinearServiceImpl {
+    async fn get_issues(&self, issue_keys: Vec<String>) -> Result<Vec<IssueInfo>, String> {
+        if issue_keys.is_empty() {
+            return Err("No issue keys provided".to_string());
+        }
+
+        let mut issues: Vec<IssueInfo> = vec![];
+        for chunk in issue_keys.chunks(PAGE_SIZE) {
+            let filter = match Self::build_filter(chunk) {
+                Some(filter) => filter,
+                None => continue,
+            };
+            let variables = json!({ "filter": filter, "first": PAGE_SIZE });
+            let response = reqwest::Client::new()
+                .post(format!("{}/graphql", self.base_url))
+                .header("Content-Type", "application/json")
+                .header("Authorization", &self.token)
+                .json(&json!({ "query": ISSUES_QUERY, "variables": variables }))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if !response.status().is_success() {
+                eprintln!(
+                    "Error fetching Linear issues: {:?}",
+                    response.text().await.unwrap_or_default()
+                );
+                return Err("Error fetching Linear issues".to_string());
+            }
+
+            let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+            let nodes = match json["data"]["issues"]["nodes"].as_array() {
+                Some(nodes) => nodes,
+                None => {
+                    eprintln!("Error fetching Linear issues: {:?}", json["errors"]);
+                    return Err("Error fetching Linear issues".to_string());
+                }
+            };
+
+            issues.extend(nodes.iter().map(|issue| {
+                IssueInfo {
+                    key: issue["identifier"].as_str().unwrap_or_default().to_string(),
+                    summary: issue["title"].as_str().unwrap_or_default().to_string(),
+                    assignee: issue["assignee"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    status: issue["state"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    reporter: issue["creator"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    priority: issue["priorityLabel"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    labels: issue["labels"]["nodes"]
+                        .as_array()
+                        .unwrap_or(&vec![])
+                        .iter()
+                        .filter_map(|label| label["name"].as_str())
+                        .map(|label| label.to_string())
+                        .collect(),
+                    ready: self.is_ready(&issue["state"]),
+                    ..Default::default()
+                }
+            }));
+        }
+
+        // Issues come back in no particular order, keep the order of the keys
+        issues.sort_by_key(|issue| {
+            issue_keys
+                .iter()
+                .position(|key| key.eq_ignore_ascii_case(&issue.key))
+        });
+
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_issues() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/graphql")
+            .match_header("Authorization", "lin_api_key")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "variables": {
+                    "filter": { "or": [
+                        { "team": { "key": { "eqIgnoreCase": "ENG" } }, "number": { "eq": 1 } },
+                        { "team": { "key": { "eqIgnoreCase": "ENG" } }, "number": { "eq": 2 } },
+                        { "team": { "key": { "eqIgnoreCase": "ENG" } }, "number": { "eq": 3 } }
+                    ] },
+                    "first": 100
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                // ENG-3 does not exist, it is simply missing from the nodes
+                json!({
+                    "data": {
+                        "issues": {
+                            "nodes": [
+                                { "identifier": "ENG-2", "title": "Add logout", "priorityLabel": "No priority", "state": { "name": "In Review", "type": "started" }, "assignee": null, "creator": { "name": "Bob" }, "labels": { "nodes": [] } },
+                                { "identifier": "ENG-1", "title": "Add login", "priorityLabel": "High", "state": { "name": "Done", "type": "completed" }, "assignee": { "name": "Alice" }, "creator": { "name": "Bob" }, "labels": { "nodes": [{ "name": "Feature" }] } }
+                            ]
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let service =
+            LinearServiceImpl::new(Some(&server.url()), Some("lin_api_key"), vec![]).unwrap();
+        let issues = service
+            .get_issues(vec![
+                "ENG-1".to_string(),
+                "ENG-2".to_string(),
+                "ENG-3".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].key, "ENG-1");
+        assert_eq!(issues[0].status, "Done");
+        assert!(issues[0].ready);
+        assert_eq!(issues[0].summary, "Add login");
+        assert_eq!(issues[0].assignee, "Alice");
+        assert_eq!(issues[0].priority, "High");
+        assert_eq!(issues[0].labels, vec!["Feature"]);
+        assert_eq!(issues[1].key, "ENG-2");
+        assert_eq!(issues[1].assignee, "");
+        assert!(!issues[1].ready);
+    }
+
+    #[test]
+    fn test_build_filter_skips_invalid_keys() {
+        assert!(LinearServiceImpl::build_filter(&["ENG".to_string()]).is_none());
+        assert_eq!(
+            LinearServiceImpl::build_filter(&["ENG".to_string(), "MY-TEAM-7".to_string()]),
+            Some(json!({ "or": [
+                { "team": { "key": { "eqIgnoreCase": "MY-TEAM" } }, "number": { "eq": 7 } }
+            ] }))
+        );
+    }
+
+    #[test]
+    fn test_ready_states() {
+        let service =
+            LinearServiceImpl::new(None, Some("key"), vec!["In Review".to_string()]).unwrap();
+
+        assert_eq!(service.base_url, DEFAULT_SERVER);
+        assert!(service.is_ready(&json!({ "name": "in review", "type": "started" })));
+        assert!(!service.is_ready(&json!({ "name": "Done", "type": "completed" })));
+    }
+}
@@ -0,0 +1,593 @@
+use crate::api::github_service::{
+    FindFirstPrOfCommitResult, GetCommitResult, GetCommitsSinceResult, GetIssueResult,
+    GithubService, SearchPullRequestResult,
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use tokio::process::Command;
+
+/// `GithubService` backed by a local clone of the repository
+///
+/// Commits and file contents are read with `git`, so owner and repository names are ignored.
+/// PR metadata is fetched from the API service if one is given, otherwise it is left empty.
+pub struct LocalGitServiceImpl {
+    repo_path: String,
+    pr_service: Option<Box<dyn GithubService>>,
+}
+
+impl LocalGitServiceImpl {
+    pub fn new(repo_path: &str, pr_service: Option<Box<dyn GithubService>>) -> Self {
+        LocalGitServiceImpl {
+            repo_path: repo_path.to_string(),
+            pr_service,
+        }
+    }
+
+    async fn git(&self, args: &[&str]) -> anyhow::Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_path)
+            .args(args)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Prefer the remote default branch, so that a checked out feature branch does not affect the result
+    async fn default_branch(&self) -> &'static str {
+        match self
+            .git(&["rev-parse", "--verify", "--quiet", "origin/HEAD"])
+            .await
+        {
+            Ok(_) => "origin/HEAD",
+            Err(_) => "HEAD",
+        }
+    }
+}
+
+#[async_trait]
+impl GithubService for LocalGitServiceImpl {
+    async fn get_commit(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        commit_sha: &str,
+        with_pr: bool,
+    ) -> anyhow::Result<GetCommitResult> {
+        let output = self
+            .git(&["show", "-s", "--format=%H%x00%cI%x00%ae%x00%B", commit_sha])
+            .await?;
+
+        let mut parts = output.splitn(4, '\0');
+        let (sha, date, author_email, message) =
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(sha), Some(date), Some(author_email), Some(message)) => {
+                    (sha, date, author_email, message)
+                }
+                _ => return Err(anyhow!("Unexpected output of git show: {}", output)),
+            };
+        let date_time: DateTime<Utc> = DateTime::parse_from_rfc3339(date)?.with_timezone(&Utc);
+
+        let pr_info = if with_pr {
+            self.find_first_pr_of_commit(owner_name, repo_name, sha)
+                .await?
+        } else {
+            FindFirstPrOfCommitResult::default()
+        };
+
+        Ok(GetCommitResult {
+            date_time,
+            author_email: author_email.to_string(),
+            sha: sha.to_string(),
+            full_message: message.trim_end().to_string(),
+            pr_title: pr_info.pr_title,
+            pr_body: pr_info.pr_body,
+            pr_number: pr_info.pr_number,
+            pr_url: pr_info.pr_url,
+            pr_labels: pr_info.pr_labels,
+            pr_merged_by: pr_info.pr_merged_by,
+            pr_reviewers: pr_info.pr_reviewers,
+            pr_head_branch: pr_info.pr_head_branch,
+            pr_merged_at: pr_info.pr_merged_at,
+        })
+    }
+
+    async fn get_commits_since(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        date_time: DateTime<Utc>,
+    ) -> anyhow::Result<GetCommitsSinceResult> {
+        let since = format!("--since={}", date_time.to_rfc3339());
+        let branch = self.default_branch().await;
+        let output = self.git(&["log", &since, "--format=%H %P", branch]).await?;
+
+        let mut commit_shas: Vec<String> = vec![];
+        let mut commit_parents: HashMap<String, Vec<String>> = HashMap::new();
+        for line in output.lines() {
+            let mut shas = line.split_whitespace().map(|sha| sha.to_string());
+            if let Some(sha) = shas.next() {
+                commit_shas.push(sha.clone());
+                commit_parents.insert(sha, shas.collect());
+            }
+        }
+
+        Ok(GetCommitsSinceResult {
+            commit_shas,
+            commit_parents,
+        })
+    }
+
+    async fn get_contents(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<String> {
+        let branch = self.default_branch().await;
+        self.git(&["show", &format!("{}:{}", branch, file_path)])
+            .await
+    }
+
+    async fn get_contents_at(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        file_path: &str,
+        git_ref: &str,
+    ) -> anyhow::Result<String> {
+        self.git(&["show", &format!("{}:{}", git_ref, file_path)])
+            .await
+    }
+
+    async fn get_file_history(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        file_path: &str,
+    ) -> anyhow::Result<Vec<GetCommitResult>> {
+        let branch = self.default_branch().await;
+        // Commits are separated by NUL, as their messages may span several lines
+        let output = self
+            .git(&[
+                "log",
+                "-z",
+                "--format=%H%x1f%cI%x1f%ae%x1f%B",
+                branch,
+                "--",
+                file_path,
+            ])
+            .await?;
+
+        output
+            .split('\0')
+            .filter(|commit| !commit.trim().is_empty())
+            .map(|commit| {
+                let parts: Vec<&str> = commit.splitn(4, '\x1f').collect();
+                match parts.as_slice() {
+                    [sha, date, author_email, message] => Ok(GetCommitResult {
+                        date_time: DateTime::parse_from_rfc3339(date)?.with_timezone(&Utc),
+                        author_email: author_email.to_string(),
+                        sha: sha.trim_start().to_string(),
+                        full_message: message.trim_end().to_string(),
+                        ..Default::default()
+                    }),
+                    _ => Err(anyhow!("Unexpected output of git log: {}", commit)),
+                }
+            })
+            .collect()
+    }
+
+    async fn search_commits(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let branch = self.default_branch().await;
+        let grep = format!("--grep={}", text);
+        let output = self
+            .git(&["log", "--fixed-strings", &grep, "--format=%H", branch])
+            .await?;
+
+        Ok(output.lines().map(|sha| sha.to_string()).collect())
+    }
+
+    async fn search_pull_requests(
+        &self,
+        owner_name: &str,
+        repo_name: &str,
+        text: &str,
+    ) -> anyhow::Result<Vec<SearchPullRequestResult>> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .search_pull_requests(owner_name, repo_name, text)
+                    .await
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn is_ancestor(
+        &self,
+        _owner_name: &str,
+        _repo_name: &str,
+        ancestor_sha: &str,
+        descendant_sha: &str,
+    ) -> anyhow::Result<bool> {
+        let merge_base = self
+            .git(&["merge-base", ancestor_sha, descendant_sha])
+            .await?;
+        let ancestor = self
+            .git(&[
+                "rev-parse",
+                "--verify",
+                &format!("{}^{{commit}}", ancestor_sha),
+            ])
+            .await?;
+
+        Ok(merge_base.trim() == ancestor.trim())
+    }
+
+    async fn find_first_pr_of_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+    ) -> anyhow::Result<FindFirstPrOfCommitResult> {
+        let pr_service = match &self.pr_service {
+            Some(pr_service) => pr_service,
+            None => return Ok(FindFirstPrOfCommitResult::default()),
+        };
+
+        match pr_service
+            .find_first_pr_of_commit(owner, repo, commit_sha)
+            .await
+        {
+            Ok(pr_info) => Ok(pr_info),
+            Err(err) => {
+                eprintln!("Error fetching PR of commit {}: {:?}", commit_sha, err);
+                Ok(FindFirstPrOfCommitResult::default())
+            }
+        }
+    }
+
+    async fn get_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: u64,
+    ) -> anyhow::Result<GetIssueResult> {
+        match &self.pr_service {
+            Some(pr_service) => pr_service.get_issue(owner, repo, issue_number).await,
+            None => Err(anyhow!(
+                "Issues are not available without an API service, cannot fetch {}/{}#{}",
+                owner,
+                repo,
+                issue_number
+            )),
+        }
+    }
+
+    async fn get_tag_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        match &self.pr_service {
+            Some(pr_service) => pr_service.get_tag_commit_sha(owner, repo, tag).await,
+            None => Err(anyhow!(
+                "Releases are not available without an API service, cannot fetch tag {} of {}/{}",
+                tag,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn get_latest_release_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> anyhow::Result<Option<String>> {
+        match &self.pr_service {
+            Some(pr_service) => pr_service.get_latest_release_tag(owner, repo).await,
+            None => Err(anyhow!(
+                "Releases are not available without an API service, cannot fetch the latest release of {}/{}",
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn get_release_url(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> anyhow::Result<Option<String>> {
+        match &self.pr_service {
+            Some(pr_service) => pr_service.get_release_url(owner, repo, tag).await,
+            None => Err(anyhow!(
+                "Releases are not available without an API service, cannot fetch release {} of {}/{}",
+                tag,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn create_tag(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        commit_sha: &str,
+        message: &str,
+    ) -> anyhow::Result<()> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .create_tag(owner, repo, tag, commit_sha, message)
+                    .await
+            }
+            None => Err(anyhow!(
+                "Releases are not available without an API service, cannot create tag {} of {}/{}",
+                tag,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        name: &str,
+        body: &str,
+    ) -> anyhow::Result<String> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .create_release(owner, repo, tag, name, body)
+                    .await
+            }
+            None => Err(anyhow!(
+                "Releases are not available without an API service, cannot create release {} of {}/{}",
+                tag,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> anyhow::Result<String> {
+        match &self.pr_service {
+            Some(pr_service) => pr_service.get_default_branch(owner, repo).await,
+            None => Err(anyhow!(
+                "Promotions are not available without an API service, cannot fetch the default branch of {}/{}",
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        from_branch: &str,
+    ) -> anyhow::Result<()> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .create_branch(owner, repo, branch, from_branch)
+                    .await
+            }
+            None => Err(anyhow!(
+                "Promotions are not available without an API service, cannot create branch {} of {}/{}",
+                branch,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn update_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        file_path: &str,
+        contents: &str,
+        message: &str,
+    ) -> anyhow::Result<()> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .update_file(owner, repo, branch, file_path, contents, message)
+                    .await
+            }
+            None => Err(anyhow!(
+                "Promotions are not available without an API service, cannot update {} of {}/{}",
+                file_path,
+                owner,
+                repo
+            )),
+        }
+    }
+
+    async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> anyhow::Result<String> {
+        match &self.pr_service {
+            Some(pr_service) => {
+                pr_service
+                    .create_pull_request(owner, repo, head, base, title, body)
+                    .await
+            }
+            None => Err(anyhow!(
+                "Promotions are not available without an API service, cannot open a PR of {} in {}/{}",
+                head,
+                owner,
+                repo
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::github_service::MockGithubService;
+    use futures::FutureExt;
+    use std::process;
+
+    /// Create a repository with two commits and return its path
+    fn create_repo(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("deppy-{}-{}", name, process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(path.join("apps")).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let git = |args: &[&str], date: &str| {
+            let status = process::Command::new("git")
+                .arg("-C")
+                .arg(&path)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@test.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@test.com")
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+
+        git(&["init", "-q"], "2021-08-01T00:00:00Z");
+        std::fs::write(format!("{}/apps/config.json", path), r#"{"a": 1}"#).unwrap();
+        git(&["add", "."], "2021-08-01T00:00:00Z");
+        git(
+            &["commit", "-q", "-m", "FOO-1: first\n\nDetails"],
+            "2021-08-01T00:00:00Z",
+        );
+        git(
+            &["commit", "-q", "--allow-empty", "-m", "FOO-2: second"],
+            "2021-08-02T00:00:00Z",
+        );
+
+        path
+    }
+
+    #[tokio::test]
+    async fn test_get_commits_since_and_get_commit() {
+        let path = create_repo("commits");
+        let service = LocalGitServiceImpl::new(&path, None);
+
+        let since: DateTime<Utc> = "2021-08-01T00:00:00Z".parse().unwrap();
+        let commits = service.get_commits_since("", "", since).await.unwrap();
+        assert_eq!(commits.commit_shas.len(), 2);
+        assert_eq!(
+            commits.commit_parents[&commits.commit_shas[0]],
+            vec![commits.commit_shas[1].clone()]
+        );
+
+        let commit = service
+            .get_commit("", "", &commits.commit_shas[1], true)
+            .await
+            .unwrap();
+        assert_eq!(commit.sha, commits.commit_shas[1]);
+        assert_eq!(commit.author_email, "test@test.com");
+        assert_eq!(commit.full_message, "FOO-1: first\n\nDetails");
+        assert_eq!(commit.date_time, since);
+        assert_eq!(commit.pr_number, None);
+
+        let contents = service
+            .get_contents("", "", "apps/config.json")
+            .await
+            .unwrap();
+        assert_eq!(contents, r#"{"a": 1}"#);
+
+        // Only the first commit touched the file
+        let history = service
+            .get_file_history("", "", "apps/config.json")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].sha, commits.commit_shas[1]);
+        assert_eq!(history[0].full_message, "FOO-1: first\n\nDetails");
+        let contents = service
+            .get_contents_at("", "", "apps/config.json", &history[0].sha)
+            .await
+            .unwrap();
+        assert_eq!(contents, r#"{"a": 1}"#);
+
+        let shas = service.search_commits("", "", "FOO-2").await.unwrap();
+        assert_eq!(shas, vec![commits.commit_shas[0].clone()]);
+        assert!(service
+            .is_ancestor("", "", &commits.commit_shas[1], &commits.commit_shas[0])
+            .await
+            .unwrap());
+        assert!(!service
+            .is_ancestor("", "", &commits.commit_shas[0], &commits.commit_shas[1])
+            .await
+            .unwrap());
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_commit_with_pr_fallback() {
+        let path = create_repo("pr");
+        let mut pr_service = MockGithubService::new();
+        pr_service
+            .expect_find_first_pr_of_commit()
+            .times(1)
+            .returning(|_, _, _| {
+                async {
+                    Ok(FindFirstPrOfCommitResult {
+                        pr_number: Some(42),
+                        pr_title: "FOO-2: second (#42)".to_string(),
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+        let service = LocalGitServiceImpl::new(&path, Some(Box::new(pr_service)));
+
+        let commit = service
+            .get_commit("jrumjantsev", "foo", "HEAD", true)
+            .await
+            .unwrap();
+
+        assert_eq!(commit.full_message, "FOO-2: second");
+        assert_eq!(commit.pr_number, Some(42));
+        assert_eq!(commit.pr_title, "FOO-2: second (#42)");
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
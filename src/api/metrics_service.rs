@@ -0,0 +1,315 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A deployment in the range of the metrics
+#[derive(Debug, Clone, Default)]
+pub struct DeploymentSample {
+    pub date_time: DateTime<Utc>,
+    /// Dates of the commits shipped by the deployment
+    pub commit_dates: Vec<DateTime<Utc>>,
+    /// Whether a commit of the deployment was reverted later
+    pub failed: bool,
+}
+
+/// Lead time, deployment frequency, batch size and change failure rate of a project in an env
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeploymentMetrics {
+    pub project: String,
+    pub env: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub deployments: usize,
+    pub deployments_per_week: f64,
+    pub commits: usize,
+    /// Commits per deployment
+    pub average_batch_size: Option<f64>,
+    /// Hours from a commit to its deployment
+    pub median_lead_time_hours: Option<f64>,
+    pub average_lead_time_hours: Option<f64>,
+    pub failed_deployments: usize,
+    /// Share of the deployments with commits that shipped a reverted commit
+    pub change_failure_rate: Option<f64>,
+}
+
+/// Output of the `metrics` command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MetricsFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl MetricsFormat {
+    pub fn render(&self, metrics: &[DeploymentMetrics]) -> anyhow::Result<String> {
+        match self {
+            MetricsFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(metrics)?)),
+            MetricsFormat::Csv => {
+                let mut lines = vec![CSV_HEADER.join(",")];
+                lines.extend(metrics.iter().map(|metrics| {
+                    [
+                        metrics.project.clone(),
+                        metrics.env.clone(),
+                        metrics.since.to_rfc3339(),
+                        metrics.until.to_rfc3339(),
+                        metrics.deployments.to_string(),
+                        format!("{:.2}", metrics.deployments_per_week),
+                        metrics.commits.to_string(),
+                        optional(metrics.average_batch_size, ""),
+                        optional(metrics.median_lead_time_hours, ""),
+                        optional(metrics.average_lead_time_hours, ""),
+                        metrics.failed_deployments.to_string(),
+                        optional(metrics.change_failure_rate, ""),
+                    ]
+                    .join(",")
+                }));
+                Ok(format!("{}\n", lines.join("\n")))
+            }
+            MetricsFormat::Text => {
+                let mut lines: Vec<String> = vec![];
+                for metrics in metrics {
+                    lines.push(format!(
+                        "{} in {} from {} to {}:",
+                        metrics.project,
+                        metrics.env,
+                        metrics.since.format("%Y-%m-%d"),
+                        metrics.until.format("%Y-%m-%d")
+                    ));
+                    lines.push(format!(
+                        "  Deployments: {} ({:.2} per week)",
+                        metrics.deployments, metrics.deployments_per_week
+                    ));
+                    lines.push(format!(
+                        "  Batch size: {} commits per deployment ({} commits)",
+                        optional(metrics.average_batch_size, "n/a"),
+                        metrics.commits
+                    ));
+                    lines.push(format!(
+                        "  Lead time: {} hours median, {} hours average",
+                        optional(metrics.median_lead_time_hours, "n/a"),
+                        optional(metrics.average_lead_time_hours, "n/a")
+                    ));
+                    lines.push(format!(
+                        "  Change failure rate: {} ({} failed deployments)",
+                        metrics
+                            .change_failure_rate
+                            .map(|rate| format!("{:.0}%", rate * 100.0))
+                            .unwrap_or("n/a".to_string()),
+                        metrics.failed_deployments
+                    ));
+                }
+                Ok(format!("{}\n", lines.join("\n")))
+            }
+        }
+    }
+}
+
+pub trait MetricsService: Sync + Send {
+    fn compute(
+        &self,
+        project: &str,
+        env: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        samples: &[DeploymentSample],
+    ) -> DeploymentMetrics;
+}
+
+pub struct MetricsServiceImpl;
+
+impl MetricsServiceImpl {
+    pub fn new() -> Self {
+        MetricsServiceImpl
+    }
+}
+
+impl MetricsService for MetricsServiceImpl {
+    fn compute(
+        &self,
+        project: &str,
+        env: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        samples: &[DeploymentSample],
+    ) -> DeploymentMetrics {
+        let mut lead_times_hours: Vec<f64> = samples
+            .iter()
+            .flat_map(|sample| {
+                sample.commit_dates.iter().map(|commit_date| {
+                    (sample.date_time - *commit_date).num_minutes() as f64 / 60.0
+                })
+            })
+            .collect();
+        lead_times_hours.sort_by(|a, b| a.total_cmp(b));
+
+        let weeks = (until - since).num_minutes() as f64 / (60.0 * 24.0 * 7.0);
+        let commits = lead_times_hours.len();
+        // Deployments without commits, e.g. rollbacks, cannot fail a change
+        let deployments_with_commits = samples
+            .iter()
+            .filter(|sample| !sample.commit_dates.is_empty())
+            .count();
+        let failed_deployments = samples.iter().filter(|sample| sample.failed).count();
+
+        DeploymentMetrics {
+            project: project.to_string(),
+            env: env.to_string(),
+            since,
+            until,
+            deployments: samples.len(),
+            deployments_per_week: if weeks > 0.0 {
+                samples.len() as f64 / weeks
+            } else {
+                0.0
+            },
+            commits,
+            average_batch_size: ratio(commits, samples.len()),
+            median_lead_time_hours: median(&lead_times_hours),
+            average_lead_time_hours: ratio_f64(lead_times_hours.iter().sum(), commits),
+            failed_deployments,
+            change_failure_rate: ratio(failed_deployments, deployments_with_commits),
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 12] = [
+    "project",
+    "env",
+    "since",
+    "until",
+    "deployments",
+    "deployments_per_week",
+    "commits",
+    "average_batch_size",
+    "median_lead_time_hours",
+    "average_lead_time_hours",
+    "failed_deployments",
+    "change_failure_rate",
+];
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    ratio_f64(numerator as f64, denominator)
+}
+
+fn ratio_f64(numerator: f64, denominator: usize) -> Option<f64> {
+    if denominator == 0 {
+        None
+    } else {
+        Some(numerator / denominator as f64)
+    }
+}
+
+/// Median of sorted values
+fn median(values: &[f64]) -> Option<f64> {
+    match values.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(values[len / 2]),
+        len => Some((values[len / 2 - 1] + values[len / 2]) / 2.0),
+    }
+}
+
+fn optional(value: Option<f64>, empty: &str) -> String {
+    value
+        .map(|value| format!("{:.2}", value))
+        .unwrap_or(empty.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn metrics() -> DeploymentMetrics {
+        let samples = vec![
+            DeploymentSample {
+                date_time: date("2026-10-08T12:00:00Z"),
+                commit_dates: vec![date("2026-10-08T10:00:00Z")],
+                failed: true,
+            },
+            DeploymentSample {
+                date_time: date("2026-10-05T12:00:00Z"),
+                commit_dates: vec![
+                    date("2026-10-05T11:00:00Z"),
+                    date("2026-10-04T12:00:00Z"),
+                    date("2026-10-05T08:00:00Z"),
+                ],
+                failed: false,
+            },
+            // Rollback
+            DeploymentSample {
+                date_time: date("2026-10-02T12:00:00Z"),
+                commit_dates: vec![],
+                failed: false,
+            },
+        ];
+
+        MetricsServiceImpl::new().compute(
+            "foo-web",
+            "prod",
+            date("2026-10-01T00:00:00Z"),
+            date("2026-10-15T00:00:00Z"),
+            &samples,
+        )
+    }
+
+    #[test]
+    fn test_compute() {
+        let metrics = metrics();
+
+        assert_eq!(metrics.deployments, 3);
+        assert_eq!(metrics.deployments_per_week, 1.5);
+        assert_eq!(metrics.commits, 4);
+        assert_eq!(metrics.average_batch_size, Some(4.0 / 3.0));
+        // Lead times of 1, 2, 4 and 24 hours
+        assert_eq!(metrics.median_lead_time_hours, Some(3.0));
+        assert_eq!(metrics.average_lead_time_hours, Some(7.75));
+        assert_eq!(metrics.failed_deployments, 1);
+        assert_eq!(metrics.change_failure_rate, Some(0.5));
+    }
+
+    #[test]
+    fn test_compute_without_deployments() {
+        let metrics = MetricsServiceImpl::new().compute(
+            "foo-web",
+            "prod",
+            date("2026-10-01T00:00:00Z"),
+            date("2026-10-15T00:00:00Z"),
+            &[],
+        );
+
+        assert_eq!(metrics.deployments_per_week, 0.0);
+        assert_eq!(metrics.median_lead_time_hours, None);
+        assert_eq!(metrics.change_failure_rate, None);
+    }
+
+    #[test]
+    fn test_render() {
+        let metrics = vec![metrics()];
+
+        assert_eq!(
+            MetricsFormat::Csv.render(&metrics).unwrap(),
+            "project,env,since,until,deployments,deployments_per_week,commits,average_batch_size,median_lead_time_hours,average_lead_time_hours,failed_deployments,change_failure_rate\n\
+             foo-web,prod,2026-10-01T00:00:00+00:00,2026-10-15T00:00:00+00:00,3,1.50,4,1.33,3.00,7.75,1,0.50\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&MetricsFormat::Json.render(&metrics).unwrap()).unwrap();
+        assert_eq!(json[0]["project"], "foo-web");
+        assert_eq!(json[0]["median_lead_time_hours"], 3.0);
+
+        let text = MetricsFormat::Text.render(&metrics).unwrap();
+        assert_eq!(
+            text.lines().collect::<Vec<&str>>(),
+            vec![
+                "foo-web in prod from 2026-10-01 to 2026-10-15:",
+                "  Deployments: 3 (1.50 per week)",
+                "  Batch size: 1.33 commits per deployment (4 commits)",
+                "  Lead time: 3.00 hours median, 7.75 hours average",
+                "  Change failure rate: 50% (1 failed deployments)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use crate::api::result_printer_service::{TicketCategory, TicketInfo};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Verdict of the release gate, ordered from the least to the most restrictive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    #[default]
+    Ship,
+    Warn,
+    Hold,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Ship => write!(f, "SHIP"),
+            Verdict::Warn => write!(f, "WARN"),
+            Verdict::Hold => write!(f, "HOLD"),
+        }
+    }
+}
+
+/// What to do with commits that do not reference any ticket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum UntrackedPolicy {
+    /// Ship them, they do not block the safe to deploy cut point either
+    Allow,
+    #[default]
+    Warn,
+    Block,
+}
+
+impl UntrackedPolicy {
+    fn verdict(&self) -> Verdict {
+        match self {
+            UntrackedPolicy::Allow => Verdict::Ship,
+            UntrackedPolicy::Warn => Verdict::Warn,
+            UntrackedPolicy::Block => Verdict::Hold,
+        }
+    }
+}
+
+/// Commits that do not need a ticket
+#[derive(Debug, Clone, Default)]
+pub struct UntrackedAllowlist {
+    /// Conventional commit types, e.g. `chore` exempts `chore: ...` and `chore(deps): ...`
+    pub commit_types: Vec<String>,
+    /// Parts of author emails, e.g. `dependabot`
+    pub authors: Vec<String>,
+}
+
+impl UntrackedAllowlist {
+    fn contains(&self, commit_message: &str, author_email: &str) -> bool {
+        let subject = commit_message.lines().next().unwrap_or("");
+        let type_regex = Regex::new(r"^([A-Za-z]+)(\([^)]*\))?!?:").unwrap();
+        let has_allowed_type = type_regex.captures(subject).is_some_and(|captures| {
+            self.commit_types
+                .iter()
+                .any(|commit_type| commit_type.eq_ignore_ascii_case(&captures[1]))
+        });
+        let author_email = author_email.to_lowercase();
+        let has_allowed_author = self
+            .authors
+            .iter()
+            .any(|author| author_email.contains(&author.to_lowercase()));
+
+        has_allowed_type || has_allowed_author
+    }
+}
+
+/// A single gating rule
+///
+/// All the conditions that are set must match for the rule to apply.
+/// Unset conditions are ignored, so a rule without conditions applies to every commit.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GateRule {
+    /// PR must carry this label
+    #[serde(default)]
+    pub label: Option<String>,
+    /// PR must not carry this label
+    #[serde(default)]
+    pub not_label: Option<String>,
+    /// Jira ticket readiness must be equal to this value
+    #[serde(default)]
+    pub ready: Option<bool>,
+    /// Jira ticket status must be equal to this value (case-insensitive)
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Ticket type must be equal to this value (case-insensitive), e.g. `Bug`
+    #[serde(default)]
+    pub issue_type: Option<String>,
+    /// Ticket priority must be equal to this value (case-insensitive), e.g. `Blocker`
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Ticket must carry this label (case-insensitive)
+    #[serde(default)]
+    pub ticket_label: Option<String>,
+    /// Ticket must belong to this component (case-insensitive)
+    #[serde(default)]
+    pub component: Option<String>,
+    /// Extra ticket fields (see `JIRA_EXTRA_FIELDS`) must be equal to these values (case-insensitive)
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateRulesFile {
+    rules: Vec<GateRule>,
+}
+
+impl GateRule {
+    /// Whether the rule is about the ticket, which makes it irrelevant to commits without one
+    fn has_ticket_conditions(&self) -> bool {
+        self.ready.is_some()
+            || self.status.is_some()
+            || self.issue_type.is_some()
+            || self.priority.is_some()
+            || self.ticket_label.is_some()
+            || self.component.is_some()
+            || !self.fields.is_empty()
+    }
+
+    fn matches(&self, commit: &TicketInfo) -> bool {
+        let has_label = |label: &String| {
+            commit
+                .pr_labels
+                .iter()
+                .any(|l| l.eq_ignore_ascii_case(label))
+        };
+
+        let ticket = &commit.ticket_details;
+
+        self.label.as_ref().is_none_or(has_label)
+            && !self.not_label.as_ref().is_some_and(has_label)
+            && self.ready.is_none_or(|ready| ready == commit.ticket_ready)
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| status.eq_ignore_ascii_case(&commit.ticket_status))
+            && self
+                .issue_type
+                .as_ref()
+                .is_none_or(|issue_type| issue_type.eq_ignore_ascii_case(&ticket.issue_type))
+            && self
+                .priority
+                .as_ref()
+                .is_none_or(|priority| priority.eq_ignore_ascii_case(&ticket.priority))
+            && self
+                .ticket_label
+                .as_ref()
+                .is_none_or(|label| contains_ignore_case(&ticket.labels, label))
+            && self
+                .component
+                .as_ref()
+                .is_none_or(|component| contains_ignore_case(&ticket.components, component))
+            && self.fields.iter().all(|(field, value)| {
+                ticket
+                    .extra_fields
+                    .get(field)
+                    .is_some_and(|actual| actual.eq_ignore_ascii_case(value))
+            })
+    }
+}
+
+fn contains_ignore_case(values: &[String], value: &str) -> bool {
+    values.iter().any(|v| v.eq_ignore_ascii_case(value))
+}
+
+pub trait ReleaseGateService: Sync + Send {
+    /// Evaluate the verdict of a single commit
+    ///
+    /// The most restrictive verdict of all the matching rules wins.
+    /// If no rule matches, the commit can be shipped.
+    fn evaluate_commit(&self, commit: &TicketInfo) -> Verdict;
+
+    /// Evaluate the verdict of the whole release, which is the most restrictive commit verdict
+    fn evaluate_release(&self, verdicts: &[Verdict]) -> Verdict {
+        verdicts.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Whether a commit without a ticket is allowed to have none
+    fn is_exempt_from_ticket(&self, commit_message: &str, author_email: &str) -> bool;
+
+    fn untracked_policy(&self) -> UntrackedPolicy;
+}
+
+pub struct ReleaseGateServiceImpl {
+    rules: Vec<GateRule>,
+    untracked_policy: UntrackedPolicy,
+    untracked_allowlist: UntrackedAllowlist,
+}
+
+impl ReleaseGateServiceImpl {
+    pub fn new(rules: Vec<GateRule>) -> Self {
+        ReleaseGateServiceImpl {
+            rules,
+            untracked_policy: UntrackedPolicy::default(),
+            untracked_allowlist: UntrackedAllowlist::default(),
+        }
+    }
+
+    pub fn with_untracked(
+        self,
+        untracked_policy: UntrackedPolicy,
+        untracked_allowlist: UntrackedAllowlist,
+    ) -> Self {
+        ReleaseGateServiceImpl {
+            untracked_policy,
+            untracked_allowlist,
+            ..self
+        }
+    }
+
+    /// Load the rules from a JSON file, see `release-rules.example.json`
+    /// Falls back to the default rules if no file is given
+    pub fn from_file(path: Option<&str>) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)?;
+                let rules_file: GateRulesFile = serde_json::from_str(&contents)?;
+                Ok(Self::new(rules_file.rules))
+            }
+            None => Ok(Self::new(Self::default_rules())),
+        }
+    }
+
+    /// Default rules:
+    /// - `do-not-deploy` label holds the commit
+    /// - `needs-migration` label warns about the commit
+    /// - not ready Jira ticket warns about the commit, unless the PR is `feature-flagged`
+    pub fn default_rules() -> Vec<GateRule> {
+        vec![
+            GateRule {
+                label: Some("do-not-deploy".to_string()),
+                verdict: Verdict::Hold,
+                ..Default::default()
+            },
+            GateRule {
+                label: Some("needs-migration".to_string()),
+                verdict: Verdict::Warn,
+                ..Default::default()
+            },
+            GateRule {
+                not_label: Some("feature-flagged".to_string()),
+                ready: Some(false),
+                verdict: Verdict::Warn,
+                ..Default::default()
+            },
+        ]
+    }
+}
+
+impl ReleaseGateService for ReleaseGateServiceImpl {
+    /// Commits without a ticket are only subject to the rules without ticket conditions,
+    /// untracked commits get the verdict of the untracked policy on top of that
+    fn evaluate_commit(&self, commit: &TicketInfo) -> Verdict {
+        let rules_verdict = self
+            .rules
+            .iter()
+            .filter(|rule| {
+                commit.ticket_category == TicketCategory::Tracked || !rule.has_ticket_conditions()
+            })
+            .filter(|rule| rule.matches(commit))
+            .map(|rule| rule.verdict)
+            .max()
+            .unwrap_or_default();
+
+        match commit.ticket_category {
+            TicketCategory::Untracked => rules_verdict.max(self.untracked_policy.verdict()),
+            _ => rules_verdict,
+        }
+    }
+
+    fn is_exempt_from_ticket(&self, commit_message: &str, author_email: &str) -> bool {
+        self.untracked_allowlist
+            .contains(commit_message, author_email)
+    }
+
+    fn untracked_policy(&self) -> UntrackedPolicy {
+        self.untracked_policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::issue_tracker_service::IssueInfo;
+
+    fn ticket_info<'a>(labels: &'a [String], ready: bool, status: &str) -> TicketInfo<'a> {
+        TicketInfo {
+            commit_sha: "123",
+            ticket_key: "FOO-1".to_string(),
+            ticket_ready: ready,
+            ticket_status: status.to_string(),
+            pr_labels: labels,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let service = ReleaseGateServiceImpl::from_file(None).unwrap();
+        let do_not_deploy = vec!["Do-Not-Deploy".to_string()];
+        let feature_flagged = vec!["feature-flagged".to_string()];
+        let needs_migration = vec!["needs-migration".to_string()];
+
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&[], true, "Done")),
+            Verdict::Ship
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&[], false, "Open")),
+            Verdict::Warn
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&feature_flagged, false, "Open")),
+            Verdict::Ship
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&needs_migration, true, "Done")),
+            Verdict::Warn
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&do_not_deploy, true, "Done")),
+            Verdict::Hold
+        );
+    }
+
+    #[test]
+    fn test_rules_from_json() {
+        let rules_file: GateRulesFile = serde_json::from_str(
+            r#"{"rules": [
+                {"status": "in progress", "verdict": "hold"},
+                {"ready": false, "verdict": "warn"}
+            ]}"#,
+        )
+        .unwrap();
+        let service = ReleaseGateServiceImpl::new(rules_file.rules);
+
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&[], false, "In Progress")),
+            Verdict::Hold
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&[], false, "Open")),
+            Verdict::Warn
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket_info(&[], true, "Done")),
+            Verdict::Ship
+        );
+
+        // A misspelled condition must not silently turn into a rule matching every commit
+        assert!(serde_json::from_str::<GateRulesFile>(
+            r#"{"rules": [{"lable": "hotfix", "verdict": "hold"}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ticket_detail_rules() {
+        let rules_file: GateRulesFile = serde_json::from_str(
+            r#"{"rules": [
+                {"issue_type": "bug", "priority": "blocker", "verdict": "hold"},
+                {"fields": {"customfield_10050": "yes"}, "verdict": "warn"},
+                {"component": "payments", "ticket_label": "risky", "verdict": "hold"}
+            ]}"#,
+        )
+        .unwrap();
+        let service = ReleaseGateServiceImpl::new(rules_file.rules);
+        let ticket = |issue_type: &str, priority: &str, risky: Option<&str>| TicketInfo {
+            commit_sha: "123",
+            ticket_key: "FOO-1".to_string(),
+            ticket_details: IssueInfo {
+                issue_type: issue_type.to_string(),
+                priority: priority.to_string(),
+                extra_fields: risky
+                    .map(|risky| {
+                        HashMap::from([("customfield_10050".to_string(), risky.to_string())])
+                    })
+                    .unwrap_or_default(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            service.evaluate_commit(&ticket("Bug", "Blocker", None)),
+            Verdict::Hold
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket("Story", "Blocker", Some("Yes"))),
+            Verdict::Warn
+        );
+        assert_eq!(
+            service.evaluate_commit(&ticket("Bug", "Major", Some("No"))),
+            Verdict::Ship
+        );
+        assert_eq!(
+            service.evaluate_commit(&TicketInfo {
+                ticket_details: IssueInfo {
+                    labels: vec!["Risky".to_string()],
+                    components: vec!["Payments".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            Verdict::Hold
+        );
+    }
+
+    #[test]
+    fn test_untracked_commits() {
+        let do_not_deploy = vec!["do-not-deploy".to_string()];
+        let untracked = |labels, ticket_category| TicketInfo {
+            commit_sha: "123",
+            pr_labels: labels,
+            ticket_category,
+            ..Default::default()
+        };
+        let warn = ReleaseGateServiceImpl::from_file(None).unwrap();
+        let allow = ReleaseGateServiceImpl::from_file(None)
+            .unwrap()
+            .with_untracked(UntrackedPolicy::Allow, UntrackedAllowlist::default());
+        let block = ReleaseGateServiceImpl::from_file(None)
+            .unwrap()
+            .with_untracked(UntrackedPolicy::Block, UntrackedAllowlist::default());
+
+        assert_eq!(
+            warn.evaluate_commit(&untracked(&[], TicketCategory::Untracked)),
+            Verdict::Warn
+        );
+        assert_eq!(
+            allow.evaluate_commit(&untracked(&[], TicketCategory::Untracked)),
+            Verdict::Ship
+        );
+        assert_eq!(
+            allow.evaluate_commit(&untracked(&do_not_deploy, TicketCategory::Untracked)),
+            Verdict::Hold
+        );
+        assert_eq!(
+            block.evaluate_commit(&untracked(&[], TicketCategory::Untracked)),
+            Verdict::Hold
+        );
+        assert_eq!(
+            block.evaluate_commit(&untracked(&[], TicketCategory::Exempt)),
+            Verdict::Ship
+        );
+    }
+
+    #[test]
+    fn test_untracked_allowlist() {
+        let service = ReleaseGateServiceImpl::new(vec![]).with_untracked(
+            UntrackedPolicy::Block,
+            UntrackedAllowlist {
+                commit_types: vec!["chore".to_string()],
+                authors: vec!["dependabot".to_string()],
+            },
+        );
+
+        assert!(service.is_exempt_from_ticket("chore: bump version", "dev@test.com"));
+        assert!(service.is_exempt_from_ticket("Chore(deps)!: drop node 16", "dev@test.com"));
+        assert!(service.is_exempt_from_ticket(
+            "Bump serde",
+            "49699333+dependabot[bot]@users.noreply.github.com"
+        ));
+        assert!(!service.is_exempt_from_ticket("fix: login", "dev@test.com"));
+        assert!(!service.is_exempt_from_ticket("chores are fun", "dev@test.com"));
+    }
+
+    #[test]
+    fn test_evaluate_release() {
+        let service = ReleaseGateServiceImpl::new(vec![]);
+
+        assert_eq!(service.evaluate_release(&[]), Verdict::Ship);
+        assert_eq!(
+            service.evaluate_release(&[Verdict::Ship, Verdict::Hold, Verdict::Warn]),
+            Verdict::Hold
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::api::github_service::GetCommitResult;
+use crate::api::issue_tracker_service::IssueInfo;
+use minijinja::Environment;
+use regex::Regex;
+use serde::Serialize;
+
+/// Built-in Markdown template, see `templates/release-notes.md.j2`
+const DEFAULT_TEMPLATE: &str = include_str!("../../templates/release-notes.md.j2");
+
+/// Section of the release notes, in the order they are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReleaseNoteCategory {
+    BreakingChanges,
+    Features,
+    BugFixes,
+    Chores,
+}
+
+impl ReleaseNoteCategory {
+    pub const ALL: [ReleaseNoteCategory; 4] = [
+        ReleaseNoteCategory::BreakingChanges,
+        ReleaseNoteCategory::Features,
+        ReleaseNoteCategory::BugFixes,
+        ReleaseNoteCategory::Chores,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ReleaseNoteCategory::BreakingChanges => "Breaking changes",
+            ReleaseNoteCategory::Features => "Features",
+            ReleaseNoteCategory::BugFixes => "Bug fixes",
+            ReleaseNoteCategory::Chores => "Chores",
+        }
+    }
+
+    /// Category of a commit and its ticket, if any
+    ///
+    /// Breaking changes are marked by a `!` after the conventional commit type, a
+    /// `BREAKING CHANGE` footer or a `breaking` PR label. Otherwise the ticket type wins over the
+    /// PR labels, which win over the conventional commit type. Anything else is a chore.
+    pub fn of(commit: &GetCommitResult, issue: Option<&IssueInfo>) -> Self {
+        let conventional_type_regex = Regex::new(r"^(\w+)(?:\([^)]*\))?(!)?:").unwrap();
+        let subject = commit.full_message.lines().next().unwrap_or_default();
+        let conventional_type = conventional_type_regex.captures(subject);
+        let has_label = |labels: &[&str]| {
+            commit
+                .pr_labels
+                .iter()
+                .any(|label| labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+        };
+
+        let is_breaking = conventional_type
+            .as_ref()
+            .is_some_and(|captures| captures.get(2).is_some())
+            || commit.full_message.contains("BREAKING CHANGE")
+            || has_label(&["breaking", "breaking-change"]);
+        if is_breaking {
+            return ReleaseNoteCategory::BreakingChanges;
+        }
+
+        let issue_type = issue.map(|issue| issue.issue_type.to_lowercase());
+        match issue_type.as_deref() {
+            Some("bug") => return ReleaseNoteCategory::BugFixes,
+            Some("story" | "feature" | "new feature" | "improvement") => {
+                return ReleaseNoteCategory::Features
+            }
+            _ => {}
+        }
+
+        if has_label(&["bug", "fix"]) {
+            return ReleaseNoteCategory::BugFixes;
+        }
+        if has_label(&["feature", "enhancement"]) {
+            return ReleaseNoteCategory::Features;
+        }
+
+        match conventional_type
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str().to_lowercase())
+            .as_deref()
+        {
+            Some("fix") => ReleaseNoteCategory::BugFixes,
+            Some("feat") => ReleaseNoteCategory::Features,
+            _ => ReleaseNoteCategory::Chores,
+        }
+    }
+}
+
+/// A change of the release notes: a ticket, a PR without a ticket or a commit without either
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReleaseNoteEntry {
+    /// Ticket summary, PR title or commit subject
+    pub title: String,
+    pub ticket_key: String,
+    pub issue_type: String,
+    pub components: Vec<String>,
+    pub pr_number: Option<u64>,
+    pub pr_url: String,
+    pub pr_labels: Vec<String>,
+    /// Commits of the change, newest first
+    pub commit_shas: Vec<String>,
+    pub authors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseNoteSection {
+    pub title: String,
+    pub entries: Vec<ReleaseNoteEntry>,
+}
+
+/// Model the release notes template is rendered with
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReleaseNotes {
+    pub project: String,
+    /// Environment the changes are not deployed to yet, if any
+    pub env: Option<String>,
+    pub from_sha: String,
+    pub to_sha: String,
+    /// Non-empty sections, in the order of `ReleaseNoteCategory::ALL`
+    pub sections: Vec<ReleaseNoteSection>,
+}
+
+pub trait ReleaseNotesService: Sync + Send {
+    fn render(&self, release_notes: &ReleaseNotes) -> anyhow::Result<String>;
+}
+
+pub struct ReleaseNotesServiceImpl {
+    template: String,
+}
+
+impl ReleaseNotesServiceImpl {
+    pub fn new(template: &str) -> Self {
+        ReleaseNotesServiceImpl {
+            template: template.to_string(),
+        }
+    }
+
+    /// Load the template from a file
+    /// Falls back to the built-in Markdown template if no file is given
+    pub fn from_file(path: Option<&str>) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => Ok(Self::new(&std::fs::read_to_string(path)?)),
+            None => Ok(Self::new(DEFAULT_TEMPLATE)),
+        }
+    }
+}
+
+impl ReleaseNotesService for ReleaseNotesServiceImpl {
+    fn render(&self, release_notes: &ReleaseNotes) -> anyhow::Result<String> {
+        let mut environment = Environment::new();
+        environment.set_trim_blocks(true);
+        environment.add_template("release-notes", &self.template)?;
+
+        Ok(environment
+            .get_template("release-notes")?
+            .render(release_notes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(message: &str, labels: &[&str]) -> GetCommitResult {
+        GetCommitResult {
+            full_message: message.to_string(),
+            pr_labels: labels.iter().map(|label| label.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_category() {
+        let bug = IssueInfo {
+            issue_type: "Bug".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("feat(api)!: drop v1", &[]), None),
+            ReleaseNoteCategory::BreakingChanges
+        );
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("FOO-1: login", &["Breaking"]), Some(&bug)),
+            ReleaseNoteCategory::BreakingChanges
+        );
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("feat: login", &[]), Some(&bug)),
+            ReleaseNoteCategory::BugFixes
+        );
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("Login", &["enhancement"]), None),
+            ReleaseNoteCategory::Features
+        );
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("feat: login", &[]), None),
+            ReleaseNoteCategory::Features
+        );
+        assert_eq!(
+            ReleaseNoteCategory::of(&commit("Bump serde", &[]), None),
+            ReleaseNoteCategory::Chores
+        );
+    }
+
+    #[test]
+    fn test_render_default_template() {
+        let release_notes = ReleaseNotes {
+            project: "foo-web".to_string(),
+            env: Some("prod".to_string()),
+            from_sha: "1111111111".to_string(),
+            to_sha: "2222222222".to_string(),
+            sections: vec![
+                ReleaseNoteSection {
+                    title: "Features".to_string(),
+                    entries: vec![ReleaseNoteEntry {
+                        title: "Add login".to_string(),
+                        ticket_key: "FOO-1".to_string(),
+                        components: vec!["auth".to_string(), "web".to_string()],
+                        pr_url: "https://github.com/jrumjantsev/foo-web/pull/1".to_string(),
+                        ..Default::default()
+                    }],
+                },
+                ReleaseNoteSection {
+                    title: "Chores".to_string(),
+                    entries: vec![ReleaseNoteEntry {
+                        title: "Bump serde".to_string(),
+                        ..Default::default()
+                    }],
+                },
+            ],
+        };
+
+        let output = ReleaseNotesServiceImpl::from_file(None)
+            .unwrap()
+            .render(&release_notes)
+            .unwrap();
+
+        assert_eq!(
+            output,
+            "## foo-web release notes\n\
+             \n\
+             Changes from `1111111` to `2222222` (prod)\n\
+             \n\
+             ### Features\n\
+             \n\
+             - [FOO-1] Add login (auth, web) https://github.com/jrumjantsev/foo-web/pull/1\n\
+             \n\
+             ### Chores\n\
+             \n\
+             - Bump serde\n"
+        );
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let service = ReleaseNotesServiceImpl::new(
+            "{% for section in sections %}{{ section.title }}: {{ section.entries | length }}\n{% endfor %}",
+        );
+        let release_notes = ReleaseNotes {
+            sections: vec![ReleaseNoteSection {
+                title: "Bug fixes".to_string(),
+                entries: vec![ReleaseNoteEntry::default(), ReleaseNoteEntry::default()],
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(service.render(&release_notes).unwrap(), "Bug fixes: 2\n");
+    }
+}
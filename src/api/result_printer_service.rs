@@ -1,20 +1,59 @@
-use crate::api::jira_service::JiraTicketInfo;
+use crate::api::issue_tracker_service::IssueInfo;
+use crate::api::release_gate_service::Verdict;
 use crate::config::Config;
-use std::collections::HashMap;
+use minijinja::Environment;
+use serde::Serialize;
 use std::option::Option;
 
-#[derive(Debug, Clone)]
+/// Whether a commit references a ticket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TicketCategory {
+    /// References at least one ticket
+    #[default]
+    Tracked,
+    /// References no ticket
+    Untracked,
+    /// References no ticket, but is allowlisted as not needing one
+    Exempt,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct TicketInfo<'a> {
     pub commit_sha: &'a str,
     pub commit_message: &'a str,
     pub author_email: &'a str,
     pub ticket_key: String,
     pub ticket_ready: bool,
+    pub ticket_status: String,
+    /// Details of the ticket, empty if the commit references none
+    pub ticket_details: IssueInfo,
+    pub ticket_category: TicketCategory,
+    pub pr_number: Option<u64>,
+    pub pr_url: &'a str,
+    pub pr_labels: &'a [String],
+    pub pr_merged_by: &'a str,
+    pub pr_reviewers: &'a [String],
+    pub verdict: Verdict,
+    /// SHA of the undeployed commit reverted by this commit
+    pub reverts: Option<&'a str>,
+    /// SHA of the undeployed commit reverting this commit
+    pub reverted_by: Option<&'a str>,
+}
+
+impl TicketInfo<'_> {
+    /// Whether the commit and its revert are both undeployed, so they cancel each other out
+    pub fn is_in_revert_pair(&self) -> bool {
+        self.reverts.is_some() || self.reverted_by.is_some()
+    }
 }
 
+/// Undeployed commits of a ticket
 #[derive(Debug)]
 pub struct TicketInfoGroup<'a> {
+    pub issue: IssueInfo,
     pub info_items: Vec<TicketInfo<'a>>,
+    /// Commits of the ticket that are already deployed, the ticket is partially deployed if any
+    pub deployed_commit_shas: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -24,23 +63,253 @@ pub struct PrintMessageFromExtractTicketsResultPayload<'a> {
     pub ticket_infos: Vec<TicketInfo<'a>>,
     pub last_commit_in_production: String,
     pub commit_sha_to_release: Option<String>,
-    pub issue_keys: HashMap<String, Vec<JiraTicketInfo>>,
+    pub release_verdict: Verdict,
+    pub safe_to_deploy_sha: Option<String>,
+    /// Ticket-centric report, one entry per ticket instead of one line per commit
+    pub ticket_groups: Option<Vec<TicketInfoGroup<'a>>>,
+}
+
+/// Built-in report templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// Plain text, one line per commit
+    #[default]
+    Text,
+    Markdown,
+    /// Slack `mrkdwn`
+    Slack,
+}
+
+impl ReportFormat {
+    /// Source of the built-in template, see `templates/`
+    pub fn template(&self) -> &'static str {
+        match self {
+            ReportFormat::Text => include_str!("../../templates/report.txt.j2"),
+            ReportFormat::Markdown => include_str!("../../templates/report.md.j2"),
+            ReportFormat::Slack => include_str!("../../templates/report.slack.j2"),
+        }
+    }
+}
+
+/// Model the report templates are rendered with
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub owner: String,
+    pub repo: String,
+    /// Compares the deployed commit with the commit to release
+    pub compare_url: String,
+    pub last_commit_in_production: String,
+    pub commit_sha_to_release: String,
+    /// Every undeployed commit, newest first
+    pub commits: Vec<ReportCommit>,
+    /// Set by `--group-by ticket`
+    pub ticket_groups: Option<Vec<ReportTicketGroup>>,
+    /// Commits of no ticket group, empty unless grouped by ticket
+    pub commits_without_ticket: Vec<ReportCommit>,
+    pub safe_to_deploy: Option<ReportSafeToDeploy>,
+    pub release_verdict: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportCommit {
+    pub sha: String,
+    pub short_sha: String,
+    pub url: String,
+    pub author_email: String,
+    /// Author email without the domain
+    pub author: String,
+    pub message: String,
+    /// 🍏 ready, 🍎 not ready, ❔ untracked, ⚪ exempt or ↩️ in a revert pair
+    pub icon: String,
+    /// `SHIP`, `WARN` or `HOLD`
+    pub verdict: String,
+    /// Ticket key, `untracked` or `exempt`
+    pub ticket_label: String,
+    pub ticket_ready: bool,
+    pub ticket_status: String,
+    pub ticket: IssueInfo,
+    /// Short SHA of the undeployed commit reverted by this commit
+    pub reverts: Option<String>,
+    /// Short SHA of the undeployed commit reverting this commit
+    pub reverted_by: Option<String>,
+    pub pr: Option<ReportPr>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportPr {
+    pub number: u64,
+    pub url: String,
+    pub labels: Vec<String>,
+    pub merged_by: String,
+    pub reviewers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportTicketGroup {
+    /// 🍏 ready or 🍎 not ready
+    pub icon: String,
+    pub issue: IssueInfo,
+    pub commits: Vec<ReportCommit>,
+    /// Commits of the ticket that are already deployed
+    pub deployed_commit_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportSafeToDeploy {
+    pub sha: String,
+    pub short_sha: String,
+    pub compare_url: String,
 }
 
 pub trait ResultPrinterService: Sync + Send {
     fn print_message_from_extract_tickets_result(
         &self,
         payload: PrintMessageFromExtractTicketsResultPayload,
-    ) -> Vec<String>;
+    ) -> anyhow::Result<Vec<String>>;
 }
 
+/// Renders the report with a minijinja template, the built-in text template by default
 pub struct ResultPrinterServiceImpl {
     pub config: Config,
+    template: String,
 }
 
 impl ResultPrinterServiceImpl {
-    pub fn new(config: Config) -> Self {
-        ResultPrinterServiceImpl { config }
+    /// Use the template file of `config.report_template` if set, or else the built-in template
+    /// of `config.report_format`
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let template = match &config.report_template {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => config.report_format.template().to_string(),
+        };
+        // Fail early on syntax errors rather than after all the API calls
+        Self::environment(&template)?;
+
+        Ok(ResultPrinterServiceImpl {
+            config: config.clone(),
+            template,
+        })
+    }
+
+    fn environment(template: &str) -> anyhow::Result<Environment<'_>> {
+        let mut environment = Environment::new();
+        environment.set_trim_blocks(true);
+        environment.set_lstrip_blocks(true);
+        environment.add_template("report", template)?;
+
+        Ok(environment)
+    }
+
+    fn report_commit(&self, owner: &str, repo: &str, commit: &TicketInfo) -> ReportCommit {
+        let icon = if commit.is_in_revert_pair() {
+            "↩️"
+        } else if commit.ticket_category == TicketCategory::Untracked {
+            "❔"
+        } else if commit.ticket_category == TicketCategory::Exempt {
+            "⚪"
+        } else if commit.ticket_ready {
+            "🍏"
+        } else {
+            "🍎"
+        };
+
+        ReportCommit {
+            sha: commit.commit_sha.to_string(),
+            short_sha: short_sha(commit.commit_sha).to_string(),
+            url: self.config.commit_url(owner, repo, commit.commit_sha),
+            author_email: commit.author_email.to_string(),
+            author: commit
+                .author_email
+                .split('@')
+                .next()
+                .unwrap_or("")
+                .to_string(),
+            message: commit.commit_message.to_string(),
+            icon: icon.to_string(),
+            verdict: commit.verdict.to_string(),
+            ticket_label: match commit.ticket_category {
+                TicketCategory::Tracked => commit.ticket_key.clone(),
+                TicketCategory::Untracked => "untracked".to_string(),
+                TicketCategory::Exempt => "exempt".to_string(),
+            },
+            ticket_ready: commit.ticket_ready,
+            ticket_status: commit.ticket_status.clone(),
+            ticket: commit.ticket_details.clone(),
+            reverts: commit.reverts.map(|sha| short_sha(sha).to_string()),
+            reverted_by: commit.reverted_by.map(|sha| short_sha(sha).to_string()),
+            pr: commit.pr_number.map(|number| ReportPr {
+                number,
+                url: commit.pr_url.to_string(),
+                labels: commit.pr_labels.to_vec(),
+                merged_by: commit.pr_merged_by.to_string(),
+                reviewers: commit.pr_reviewers.to_vec(),
+            }),
+        }
+    }
+
+    fn report(&self, payload: &PrintMessageFromExtractTicketsResultPayload) -> Report {
+        let owner = payload.owner;
+        let repo = payload.repo;
+        let last_commit_in_production = &payload.last_commit_in_production;
+        let commit_sha_to_release = payload.commit_sha_to_release.as_deref().unwrap_or("master");
+        let report_commit = |commit: &TicketInfo| self.report_commit(owner, repo, commit);
+
+        let commits_without_ticket = match &payload.ticket_groups {
+            Some(ticket_groups) => payload
+                .ticket_infos
+                .iter()
+                .filter(|commit| {
+                    !ticket_groups.iter().any(|group| {
+                        group
+                            .info_items
+                            .iter()
+                            .any(|item| item.commit_sha == commit.commit_sha)
+                    })
+                })
+                .map(report_commit)
+                .collect(),
+            None => vec![],
+        };
+
+        Report {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            compare_url: self.config.compare_url(
+                owner,
+                repo,
+                last_commit_in_production,
+                commit_sha_to_release,
+            ),
+            last_commit_in_production: last_commit_in_production.clone(),
+            commit_sha_to_release: commit_sha_to_release.to_string(),
+            commits: payload.ticket_infos.iter().map(report_commit).collect(),
+            ticket_groups: payload.ticket_groups.as_ref().map(|ticket_groups| {
+                ticket_groups
+                    .iter()
+                    .map(|group| ReportTicketGroup {
+                        icon: if group.issue.ready { "🍏" } else { "🍎" }.to_string(),
+                        issue: group.issue.clone(),
+                        commits: group.info_items.iter().map(report_commit).collect(),
+                        deployed_commit_count: group.deployed_commit_shas.len(),
+                    })
+                    .collect()
+            }),
+            commits_without_ticket,
+            safe_to_deploy: payload
+                .safe_to_deploy_sha
+                .as_deref()
+                .map(|sha| ReportSafeToDeploy {
+                    sha: sha.to_string(),
+                    short_sha: short_sha(sha).to_string(),
+                    compare_url: self.config.compare_url(
+                        owner,
+                        repo,
+                        last_commit_in_production,
+                        sha,
+                    ),
+                }),
+            release_verdict: payload.release_verdict.to_string(),
+        }
     }
 }
 
@@ -48,43 +317,412 @@ impl ResultPrinterService for ResultPrinterServiceImpl {
     fn print_message_from_extract_tickets_result(
         &self,
         payload: PrintMessageFromExtractTicketsResultPayload,
-    ) -> Vec<String> {
-        let mut output: Vec<String> = vec![];
+    ) -> anyhow::Result<Vec<String>> {
+        let output = Self::environment(&self.template)?
+            .get_template("report")?
+            .render(self.report(&payload))?;
 
-        let owner = &payload.owner;
-        let repo = &payload.repo;
-        let ticket_infos = &payload.ticket_infos;
-        let last_commit_in_production = &payload.last_commit_in_production;
-        let commit_sha_to_release = payload.commit_sha_to_release.as_deref().unwrap_or("master");
+        Ok(output.lines().map(|line| line.to_string()).collect())
+    }
+}
 
-        output.push(format!(
-            "{}/{}/{}/compare/{}...{}\n",
-            self.config.github_server,
-            owner,
-            repo,
-            last_commit_in_production,
-            commit_sha_to_release
-        ));
-
-        ticket_infos.iter().for_each(|commit| {
-            let author_username = commit.author_email.split('@').next().unwrap_or("");
-            let ticket_ready_icon = if commit.ticket_ready { "🍏" } else { "🍎" };
-            let short_commit_sha = &commit.commit_sha[..7.min(commit.commit_sha.len())];
-
-            output.push(format!(
-                "{} @{} {}/{}/{}/commit/{} ({}) - [{}] {}",
-                ticket_ready_icon,
-                author_username,
-                self.config.github_server,
-                owner,
-                repo,
-                commit.commit_sha,
-                short_commit_sha,
-                commit.ticket_key,
-                commit.commit_message
-            ));
-        });
-
-        output
+fn short_sha(sha: &str) -> &str {
+    &sha[..7.min(sha.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> Config {
+        Config {
+            github_server: "https://github.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_print_message_with_pr_details() {
+        let labels = vec!["feature-flagged".to_string()];
+        let reviewers = vec!["bob".to_string(), "carol".to_string()];
+        let service = ResultPrinterServiceImpl::from_config(&test_config()).unwrap();
+
+        let output = service
+            .print_message_from_extract_tickets_result(
+                PrintMessageFromExtractTicketsResultPayload {
+                    owner: "jrumjantsev",
+                    repo: "foo",
+                    ticket_infos: vec![TicketInfo {
+                        commit_sha: "1234567890",
+                        commit_message: "FOO-1: add login",
+                        author_email: "alice@example.com",
+                        ticket_key: "FOO-1".to_string(),
+                        ticket_ready: true,
+                        ticket_status: "Done".to_string(),
+                        pr_number: Some(42),
+                        pr_url: "https://github.com/jrumjantsev/foo/pull/42",
+                        pr_labels: &labels,
+                        pr_merged_by: "dave",
+                        pr_reviewers: &reviewers,
+                        ..Default::default()
+                    }],
+                    last_commit_in_production: "abc".to_string(),
+                    commit_sha_to_release: None,
+                    release_verdict: Verdict::Ship,
+                    safe_to_deploy_sha: Some("1234567890".to_string()),
+                    ticket_groups: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            output,
+            vec![
+                "https://github.com/jrumjantsev/foo/compare/abc...master".to_string(),
+                "".to_string(),
+                "🍏 @alice https://github.com/jrumjantsev/foo/commit/1234567890 (1234567) - [FOO-1] FOO-1: add login \
+                 | PR #42 https://github.com/jrumjantsev/foo/pull/42 | labels: feature-flagged \
+                 | merged by @dave | approved by @bob, @carol"
+                    .to_string(),
+                "".to_string(),
+                "Safe to deploy up to: 1234567 https://github.com/jrumjantsev/foo/compare/abc...1234567890"
+                    .to_string(),
+                "".to_string(),
+                "Release verdict: SHIP".to_string(),
+            ]
+        );
+    }
+
+    fn payload<'a>(
+        ticket_infos: Vec<TicketInfo<'a>>,
+    ) -> PrintMessageFromExtractTicketsResultPayload<'a> {
+        PrintMessageFromExtractTicketsResultPayload {
+            owner: "jrumjantsev",
+            repo: "foo",
+            ticket_infos,
+            last_commit_in_production: "abc".to_string(),
+            commit_sha_to_release: None,
+            release_verdict: Verdict::Warn,
+            safe_to_deploy_sha: None,
+            ticket_groups: None,
+        }
+    }
+
+    #[test]
+    fn test_print_message_with_builtin_templates() {
+        let commit = TicketInfo {
+            commit_sha: "1234567890",
+            commit_message: "FOO-1: add | remove",
+            author_email: "alice@example.com",
+            ticket_key: "FOO-1".to_string(),
+            pr_number: Some(42),
+            pr_url: "https://github.com/jrumjantsev/foo/pull/42",
+            verdict: Verdict::Warn,
+            ..Default::default()
+        };
+        let printer = |report_format| {
+            ResultPrinterServiceImpl::from_config(&Config {
+                report_format,
+                ..test_config()
+            })
+            .unwrap()
+        };
+
+        let markdown = printer(ReportFormat::Markdown)
+            .print_message_from_extract_tickets_result(payload(vec![commit.clone()]))
+            .unwrap();
+        assert_eq!(markdown[0], "## Undeployed commits of jrumjantsev/foo");
+        assert_eq!(
+            markdown[6],
+            "| 🍎 | WARN | FOO-1 | [`1234567`](https://github.com/jrumjantsev/foo/commit/1234567890) | @alice \
+             | FOO-1: add \\| remove | [#42](https://github.com/jrumjantsev/foo/pull/42) |"
+        );
+        assert_eq!(markdown.last().unwrap(), "**Release verdict:** WARN");
+
+        let slack = printer(ReportFormat::Slack)
+            .print_message_from_extract_tickets_result(payload(vec![commit]))
+            .unwrap();
+        assert_eq!(
+            slack[1],
+            "🍎 *WARN* `FOO-1` <https://github.com/jrumjantsev/foo/commit/1234567890|1234567> FOO-1: add | remove \
+             by alice (<https://github.com/jrumjantsev/foo/pull/42|#42>)"
+        );
+        assert_eq!(slack.last().unwrap(), "Release verdict: *WARN*");
+    }
+
+    #[test]
+    fn test_print_message_with_builtin_templates_and_pr_details() {
+        let labels = vec!["feature-flagged".to_string()];
+        let reviewers = vec!["bob".to_string(), "carol".to_string()];
+        let commit = TicketInfo {
+            commit_sha: "1234567890",
+            commit_message: "FOO-1: add login",
+            author_email: "alice@example.com",
+            ticket_key: "FOO-1".to_string(),
+            ticket_ready: true,
+            pr_number: Some(42),
+            pr_url: "https://github.com/jrumjantsev/foo/pull/42",
+            pr_labels: &labels,
+            pr_merged_by: "dave",
+            pr_reviewers: &reviewers,
+            ..Default::default()
+        };
+        let grouped_payload = || PrintMessageFromExtractTicketsResultPayload {
+            ticket_groups: Some(vec![TicketInfoGroup {
+                issue: IssueInfo {
+                    key: "FOO-1".to_string(),
+                    summary: "Login".to_string(),
+                    status: "Done".to_string(),
+                    ready: true,
+                    ..Default::default()
+                },
+                info_items: vec![commit.clone()],
+                deployed_commit_shas: vec![],
+            }]),
+            ..payload(vec![commit.clone()])
+        };
+        let printer = |report_format| {
+            ResultPrinterServiceImpl::from_config(&Config {
+                report_format,
+                ..test_config()
+            })
+            .unwrap()
+        };
+
+        let markdown = printer(ReportFormat::Markdown)
+            .print_message_from_extract_tickets_result(payload(vec![commit.clone()]))
+            .unwrap();
+        assert_eq!(
+            markdown[6],
+            "| 🍏 | SHIP | FOO-1 | [`1234567`](https://github.com/jrumjantsev/foo/commit/1234567890) | @alice \
+             | FOO-1: add login | [#42](https://github.com/jrumjantsev/foo/pull/42) `feature-flagged`, merged by @dave, \
+             approved by @bob, @carol |"
+        );
+
+        let slack = printer(ReportFormat::Slack)
+            .print_message_from_extract_tickets_result(payload(vec![commit.clone()]))
+            .unwrap();
+        assert_eq!(
+            slack[1],
+            "🍏 `FOO-1` <https://github.com/jrumjantsev/foo/commit/1234567890|1234567> FOO-1: add login by alice \
+             (<https://github.com/jrumjantsev/foo/pull/42|#42> `feature-flagged`, merged by dave, approved by bob, carol)"
+        );
+
+        let grouped_slack = printer(ReportFormat::Slack)
+            .print_message_from_extract_tickets_result(grouped_payload())
+            .unwrap();
+        assert_eq!(
+            grouped_slack[1..3],
+            [
+                "🍏 *FOO-1* Login _Done_",
+                "    • <https://github.com/jrumjantsev/foo/commit/1234567890|1234567> FOO-1: add login by alice \
+                 (<https://github.com/jrumjantsev/foo/pull/42|#42> `feature-flagged`, merged by dave, approved by bob, carol)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_message_with_template_file() {
+        let path = std::env::temp_dir().join("deppy-report-template-test.j2");
+        std::fs::write(
+            &path,
+            "{% for commit in commits %}{{ commit.short_sha }} {{ commit.ticket_label }}\n{% endfor %}",
+        )
+        .unwrap();
+        let service = ResultPrinterServiceImpl::from_config(&Config {
+            report_format: ReportFormat::Slack,
+            report_template: Some(path.to_string_lossy().to_string()),
+            ..test_config()
+        })
+        .unwrap();
+
+        let output = service
+            .print_message_from_extract_tickets_result(payload(vec![TicketInfo {
+                commit_sha: "1234567890",
+                ticket_category: TicketCategory::Untracked,
+                ..Default::default()
+            }]))
+            .unwrap();
+
+        assert_eq!(output, vec!["1234567 untracked"]);
+        assert!(ResultPrinterServiceImpl::from_config(&Config {
+            report_template: Some("/nonexistent/template.j2".to_string()),
+            ..test_config()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_print_message_without_pr() {
+        let service = ResultPrinterServiceImpl::from_config(&test_config()).unwrap();
+
+        let output = service
+            .print_message_from_extract_tickets_result(
+                PrintMessageFromExtractTicketsResultPayload {
+                    owner: "jrumjantsev",
+                    repo: "foo",
+                    ticket_infos: vec![TicketInfo {
+                        commit_sha: "1234567890",
+                        author_email: "alice@example.com",
+                        verdict: Verdict::Hold,
+                        ..Default::default()
+                    }],
+                    last_commit_in_production: "abc".to_string(),
+                    commit_sha_to_release: None,
+                    release_verdict: Verdict::Hold,
+                    safe_to_deploy_sha: None,
+                    ticket_groups: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            output[2],
+            "🍎 [HOLD] @alice https://github.com/jrumjantsev/foo/commit/1234567890 (1234567) - [] "
+        );
+        assert_eq!(
+            output[4],
+            "Safe to deploy up to: nothing, the oldest undeployed commit is not ready"
+        );
+        assert_eq!(output[6], "Release verdict: HOLD");
+    }
+
+    #[test]
+    fn test_print_message_grouped_by_ticket() {
+        let service = ResultPrinterServiceImpl::from_config(&test_config()).unwrap();
+        let commit = |commit_sha, commit_message| TicketInfo {
+            commit_sha,
+            commit_message,
+            ..Default::default()
+        };
+
+        let output = service
+            .print_message_from_extract_tickets_result(
+                PrintMessageFromExtractTicketsResultPayload {
+                    owner: "jrumjantsev",
+                    repo: "foo",
+                    ticket_infos: vec![
+                        commit("2222222222", "FOO-1: fix login"),
+                        commit("1111111111", "fix typo"),
+                    ],
+                    last_commit_in_production: "abc".to_string(),
+                    commit_sha_to_release: None,
+                    release_verdict: Verdict::Ship,
+                    safe_to_deploy_sha: Some("2222222222".to_string()),
+                    ticket_groups: Some(vec![TicketInfoGroup {
+                        issue: IssueInfo {
+                            key: "FOO-1".to_string(),
+                            summary: "Login".to_string(),
+                            status: "Done".to_string(),
+                            assignee: "Alice".to_string(),
+                            issue_type: "Bug".to_string(),
+                            epic: "FOO-100".to_string(),
+                            ready: true,
+                            ..Default::default()
+                        },
+                        info_items: vec![commit("2222222222", "FOO-1: fix login")],
+                        deployed_commit_shas: vec!["0000000000".to_string()],
+                    }]),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            output[2..6],
+            [
+                "🍏 [FOO-1] Login | Done | type: Bug | assignee: Alice | epic: FOO-100 | partially deployed: 1 commit(s) already live",
+                "    - https://github.com/jrumjantsev/foo/commit/2222222222 (2222222) FOO-1: fix login",
+                "Commits without a known ticket:",
+                "    - https://github.com/jrumjantsev/foo/commit/1111111111 (1111111) fix typo",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_message_untracked() {
+        let service = ResultPrinterServiceImpl::from_config(&test_config()).unwrap();
+        let commit = |commit_sha, commit_message, ticket_category| TicketInfo {
+            commit_sha,
+            commit_message,
+            author_email: "alice@example.com",
+            ticket_category,
+            ..Default::default()
+        };
+
+        let output = service
+            .print_message_from_extract_tickets_result(
+                PrintMessageFromExtractTicketsResultPayload {
+                    owner: "jrumjantsev",
+                    repo: "foo",
+                    ticket_infos: vec![
+                        TicketInfo {
+                            verdict: Verdict::Warn,
+                            ..commit("2222222222", "fix typo", TicketCategory::Untracked)
+                        },
+                        commit("1111111111", "chore: bump", TicketCategory::Exempt),
+                    ],
+                    last_commit_in_production: "abc".to_string(),
+                    commit_sha_to_release: None,
+                    release_verdict: Verdict::Warn,
+                    safe_to_deploy_sha: Some("1111111111".to_string()),
+                    ticket_groups: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            output[2],
+            "❔ [WARN] @alice https://github.com/jrumjantsev/foo/commit/2222222222 (2222222) - [untracked] fix typo"
+        );
+        assert_eq!(
+            output[3],
+            "⚪ @alice https://github.com/jrumjantsev/foo/commit/1111111111 (1111111) - [exempt] chore: bump"
+        );
+    }
+
+    #[test]
+    fn test_print_message_with_revert_pair() {
+        let service = ResultPrinterServiceImpl::from_config(&test_config()).unwrap();
+
+        let output = service
+            .print_message_from_extract_tickets_result(
+                PrintMessageFromExtractTicketsResultPayload {
+                    owner: "jrumjantsev",
+                    repo: "foo",
+                    ticket_infos: vec![
+                        TicketInfo {
+                            commit_sha: "2222222222",
+                            commit_message: "Revert \"FOO-1: add login\"",
+                            author_email: "alice@example.com",
+                            ticket_key: "FOO-1".to_string(),
+                            reverts: Some("1111111111"),
+                            ..Default::default()
+                        },
+                        TicketInfo {
+                            commit_sha: "1111111111",
+                            commit_message: "FOO-1: add login",
+                            author_email: "alice@example.com",
+                            ticket_key: "FOO-1".to_string(),
+                            reverted_by: Some("2222222222"),
+                            ..Default::default()
+                        },
+                    ],
+                    last_commit_in_production: "abc".to_string(),
+                    commit_sha_to_release: None,
+                    release_verdict: Verdict::Ship,
+                    safe_to_deploy_sha: Some("2222222222".to_string()),
+                    ticket_groups: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            output[2],
+            "↩️ [REVERTS 1111111] @alice https://github.com/jrumjantsev/foo/commit/2222222222 (2222222) - [FOO-1] Revert \"FOO-1: add login\""
+        );
+        assert_eq!(
+            output[3],
+            "↩️ [REVERTED BY 2222222] @alice https://github.com/jrumjantsev/foo/commit/1111111111 (1111111) - [FOO-1] FOO-1: add login"
+        );
     }
 }
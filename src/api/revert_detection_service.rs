@@ -0,0 +1,294 @@
+use crate::api::github_service::GetCommitResult;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevertPair {
+    /// SHA of the `Revert "..."` commit
+    pub revert_sha: String,
+    /// SHA of the commit that has been reverted
+    pub reverted_sha: String,
+}
+
+pub trait RevertDetectionService: Sync + Send {
+    /// Find the commit reverted by the given commit among the given commits, listed newest first
+    ///
+    /// The reverted commit is looked up by (in order of precedence):
+    /// - `This reverts commit <sha>` trailer
+    /// - `Reverts <owner>/<repo>#<number>` line that GitHub adds to revert PRs
+    /// - `Revert "<subject>"` subject line
+    fn find_reverted_commit<'a>(
+        &self,
+        commit: &GetCommitResult,
+        commits: &'a [GetCommitResult],
+    ) -> Option<&'a GetCommitResult>;
+
+    /// Find every revert whose reverted commit is among the given commits, including reverts of
+    /// reverts
+    fn find_direct_revert_pairs(&self, commits: &[GetCommitResult]) -> Vec<RevertPair> {
+        commits
+            .iter()
+            .filter_map(|commit| {
+                self.find_reverted_commit(commit, commits)
+                    .map(|reverted| RevertPair {
+                        revert_sha: commit.sha.clone(),
+                        reverted_sha: reverted.sha.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Find the pairs of commits that cancel each other out among the given commits
+    ///
+    /// Revert chains are resolved: a revert only cancels its reverted commit if it is live itself,
+    /// i.e. not reverted by a live revert. In `X`, `Revert "X"`, `Revert "Revert "X""`, the revert
+    /// of the revert cancels the revert, and `X` is live again.
+    fn find_revert_pairs(&self, commits: &[GetCommitResult]) -> Vec<RevertPair> {
+        let pairs = self.find_direct_revert_pairs(commits);
+        pairs
+            .iter()
+            .filter(|pair| is_live(&pair.revert_sha, &pairs, 0))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Whether a commit is not cancelled by a live revert
+fn is_live(sha: &str, pairs: &[RevertPair], depth: usize) -> bool {
+    // Bail out of cycles, which only malformed messages can produce
+    if depth > pairs.len() {
+        return true;
+    }
+
+    !pairs
+        .iter()
+        .filter(|pair| pair.reverted_sha == sha)
+        .any(|pair| is_live(&pair.revert_sha, pairs, depth + 1))
+}
+
+pub struct RevertDetectionServiceImpl {
+    trailer_regex: Regex,
+    pr_reference_regex: Regex,
+    subject_regex: Regex,
+}
+
+impl RevertDetectionServiceImpl {
+    pub fn new() -> Self {
+        Self {
+            trailer_regex: Regex::new(r"This reverts commit ([0-9a-fA-F]{7,40})").unwrap(),
+            pr_reference_regex: Regex::new(r"(?m)^Reverts [\w.-]+/[\w.-]+#(\d+)").unwrap(),
+            subject_regex: Regex::new(r#"^Revert "(.+)""#).unwrap(),
+        }
+    }
+}
+
+fn subject(message: &str) -> &str {
+    message.lines().next().unwrap_or("").trim()
+}
+
+impl RevertDetectionService for RevertDetectionServiceImpl {
+    fn find_reverted_commit<'a>(
+        &self,
+        commit: &GetCommitResult,
+        commits: &'a [GetCommitResult],
+    ) -> Option<&'a GetCommitResult> {
+        let others = || commits.iter().filter(|other| other.sha != commit.sha);
+
+        for text in [&commit.full_message, &commit.pr_body] {
+            if let Some(captures) = self.trailer_regex.captures(text) {
+                let reverted_sha = captures[1].to_lowercase();
+                if let Some(reverted) = others().find(|other| other.sha.starts_with(&reverted_sha))
+                {
+                    return Some(reverted);
+                }
+            }
+        }
+
+        if let Some(captures) = self.pr_reference_regex.captures(&commit.pr_body) {
+            let pr_number: Option<u64> = captures[1].parse().ok();
+            if let Some(reverted) =
+                others().find(|other| other.pr_number.is_some() && other.pr_number == pr_number)
+            {
+                return Some(reverted);
+            }
+        }
+
+        // A subject can come back when the change is re-landed, only the commits listed after the
+        // revert (newest first) are older than it
+        let captures = self.subject_regex.captures(subject(&commit.full_message))?;
+        let reverted_subject = &captures[1];
+        let older = commits
+            .iter()
+            .position(|other| other.sha == commit.sha)
+            .map(|index| &commits[index + 1..])
+            .unwrap_or(commits);
+        older
+            .iter()
+            .filter(|other| other.sha != commit.sha)
+            .find(|other| subject(&other.full_message) == reverted_subject)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(
+        sha: &str,
+        full_message: &str,
+        pr_number: Option<u64>,
+        pr_body: &str,
+    ) -> GetCommitResult {
+        GetCommitResult {
+            sha: sha.to_string(),
+            full_message: full_message.to_string(),
+            pr_body: pr_body.to_string(),
+            pr_number,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_revert_pairs_by_trailer() {
+        let commits = vec![
+            commit(
+                "bbb2222",
+                "Revert \"FOO-1: add login\"\n\nThis reverts commit aaa1111.",
+                None,
+                "",
+            ),
+            commit("aaa1111", "FOO-1: add something else", None, ""),
+        ];
+
+        let pairs = RevertDetectionServiceImpl::new().find_revert_pairs(&commits);
+
+        assert_eq!(
+            pairs,
+            vec![RevertPair {
+                revert_sha: "bbb2222".to_string(),
+                reverted_sha: "aaa1111".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_revert_pairs_by_subject_skips_re_landed_commit() {
+        let commits = vec![
+            commit("ccc3333", "FOO-1: add login", None, ""),
+            commit("bbb2222", "Revert \"FOO-1: add login\"", None, ""),
+            commit("aaa1111", "FOO-1: add login", None, ""),
+        ];
+
+        let pairs = RevertDetectionServiceImpl::new().find_revert_pairs(&commits);
+
+        assert_eq!(
+            pairs,
+            vec![RevertPair {
+                revert_sha: "bbb2222".to_string(),
+                reverted_sha: "aaa1111".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_revert_pairs_by_pr_reference() {
+        let commits = vec![
+            commit(
+                "bbb2222",
+                "Undo login (#43)",
+                Some(43),
+                "Reverts jrumjantsev/foo#42",
+            ),
+            commit("aaa1111", "FOO-1: add login (#42)", Some(42), ""),
+        ];
+
+        let pairs = RevertDetectionServiceImpl::new().find_revert_pairs(&commits);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].reverted_sha, "aaa1111");
+    }
+
+    #[test]
+    fn test_find_revert_pairs_by_subject() {
+        let commits = vec![
+            commit(
+                "ccc3333",
+                "Revert \"FOO-1: add login (#42)\" (#43)",
+                None,
+                "",
+            ),
+            commit("bbb2222", "FOO-2: unrelated", None, ""),
+            commit("aaa1111", "FOO-1: add login (#42)\n\nDetails", None, ""),
+        ];
+
+        let pairs = RevertDetectionServiceImpl::new().find_revert_pairs(&commits);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].revert_sha, "ccc3333");
+        assert_eq!(pairs[0].reverted_sha, "aaa1111");
+    }
+
+    #[test]
+    fn test_find_revert_pairs_resolves_revert_chains() {
+        let commits = vec![
+            commit(
+                "ddd4444",
+                "Revert \"Revert \"Revert \"FOO-2: add logout\"\"\"",
+                None,
+                "",
+            ),
+            commit(
+                "ccc3333",
+                "Revert \"Revert \"FOO-1: add login\"\"",
+                None,
+                "",
+            ),
+            commit(
+                "bbb2222",
+                "Revert \"FOO-1: add login\"\n\nThis reverts commit aaa1111.",
+                None,
+                "",
+            ),
+            commit("aaa1111", "FOO-1: add login", None, ""),
+            commit(
+                "fff6666",
+                "Revert \"Revert \"FOO-2: add logout\"\"",
+                None,
+                "",
+            ),
+            commit("eee5555", "Revert \"FOO-2: add logout\"", None, ""),
+            commit("aaa0000", "FOO-2: add logout", None, ""),
+        ];
+        let service = RevertDetectionServiceImpl::new();
+
+        assert_eq!(service.find_direct_revert_pairs(&commits).len(), 5);
+
+        let pair = |revert_sha: &str, reverted_sha: &str| RevertPair {
+            revert_sha: revert_sha.to_string(),
+            reverted_sha: reverted_sha.to_string(),
+        };
+        // FOO-1 is reverted twice, so it is live and only the reverts cancel each other out.
+        // FOO-2 is reverted three times, so it is cancelled by its first revert.
+        assert_eq!(
+            service.find_revert_pairs(&commits),
+            vec![
+                pair("ddd4444", "fff6666"),
+                pair("ccc3333", "bbb2222"),
+                pair("eee5555", "aaa0000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_revert_pairs_reverted_commit_already_deployed() {
+        let commits = vec![commit(
+            "bbb2222",
+            "Revert \"FOO-1: add login\"\n\nThis reverts commit aaa1111.",
+            None,
+            "",
+        )];
+
+        let pairs = RevertDetectionServiceImpl::new().find_revert_pairs(&commits);
+
+        assert!(pairs.is_empty());
+    }
+}
@@ -1,48 +1,315 @@
+use crate::api::github_service::GetCommitResult;
 use regex::Regex;
 
 pub trait TicketExtractionService: Sync + Send {
-    fn regex(&self) -> &Regex;
+    /// Extract the ticket keys referenced in the text, in the order of their first occurrence
+    fn extract_tickets(&self, text: &str) -> Vec<String>;
+}
 
-    fn extract_tickets(&self, text: &str) -> Vec<String> {
-        self.regex()
-            .find_iter(text)
-            .map(|m| m.as_str().to_string())
-            .collect()
+/// Part of a commit that ticket keys are extracted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TicketSource {
+    /// Trailers of the commit message, e.g. `Jira: FOO-123` or `Refs: FOO-123`
+    Trailers,
+    /// The whole commit message
+    Message,
+    PrTitle,
+    PrBody,
+    /// Head branch of the PR, e.g. `feature/FOO-123-login`
+    Branch,
+}
+
+impl TicketSource {
+    /// The most explicit references come first
+    pub const DEFAULT_PRECEDENCE: [TicketSource; 5] = [
+        TicketSource::Trailers,
+        TicketSource::Message,
+        TicketSource::PrTitle,
+        TicketSource::PrBody,
+        TicketSource::Branch,
+    ];
+
+    /// Text of the commit to extract ticket keys from
+    pub fn text(&self, commit: &GetCommitResult) -> String {
+        match self {
+            TicketSource::Trailers => commit_trailers(&commit.full_message)
+                .into_iter()
+                .map(|(_, value)| value)
+                .collect::<Vec<&str>>()
+                .join("\n"),
+            TicketSource::Message => commit.full_message.clone(),
+            TicketSource::PrTitle => commit.pr_title.clone(),
+            TicketSource::PrBody => commit.pr_body.clone(),
+            TicketSource::Branch => commit.pr_head_branch.clone(),
+        }
     }
 }
 
-pub struct FooTicketExtractionService {
-    regex: Regex,
+/// Trailers of a commit message: the `Token: value` lines of its last paragraph
+///
+/// The subject is never a trailer, and neither is a last paragraph with lines of another form.
+pub fn commit_trailers(message: &str) -> Vec<(&str, &str)> {
+    let paragraphs: Vec<&str> = message
+        .trim()
+        .split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect();
+    if paragraphs.len() < 2 {
+        return vec![];
+    }
+
+    let trailer_regex = Regex::new(r"^([A-Za-z0-9][A-Za-z0-9-]*):\s*(.+)$").unwrap();
+    let lines: Vec<&str> = paragraphs[paragraphs.len() - 1]
+        .lines()
+        .map(|line| line.trim())
+        .collect();
+    let trailers: Vec<(&str, &str)> = lines
+        .iter()
+        .filter_map(|line| trailer_regex.captures(line))
+        .map(|captures| {
+            (
+                captures.get(1).unwrap().as_str(),
+                captures.get(2).unwrap().as_str(),
+            )
+        })
+        .collect();
+
+    if trailers.len() == lines.len() {
+        trailers
+    } else {
+        vec![]
+    }
 }
 
-pub struct BarTicketExtractionService {
-    regex: Regex,
+/// Ticket numbers made of zeros only are placeholders, e.g. `FOO-0` or `FOO-000` in PR templates
+const DEFAULT_EXCLUSION: &str = r"[A-Z][A-Z0-9_]*-0+";
+
+/// Extracts ticket keys such as `FOO-123`, normalized to upper case
+///
+/// A key must not be glued to other letters or digits, so neither `xfoo-12` nor `FOO-1234567`
+/// (with the default 6 digit limit) match. Keys matching an exclusion pattern are skipped.
+pub struct TicketExtractionServiceImpl {
+    patterns: Vec<Regex>,
+    exclusions: Vec<Regex>,
 }
 
-impl FooTicketExtractionService {
-    pub fn new() -> Self {
-        Self {
-            regex: Regex::new(r"[fF][oO][oO]-\d{1,6}").unwrap(),
+impl TicketExtractionServiceImpl {
+    /// `prefixes` are project keys like `FOO`, `patterns` are custom regexes for keys that do not
+    /// follow the `PREFIX-123` format (the first capture group is the key, if any), `exclusions`
+    /// are regexes for keys that must be ignored
+    pub fn new(
+        prefixes: &[String],
+        patterns: &[String],
+        exclusions: &[String],
+    ) -> Result<Self, regex::Error> {
+        let mut all_patterns: Vec<Regex> = vec![];
+        if !prefixes.is_empty() {
+            let prefixes: Vec<String> = prefixes.iter().map(|p| regex::escape(p)).collect();
+            all_patterns.push(Regex::new(&format!(
+                r"(?i)(?:{})-\d{{1,6}}",
+                prefixes.join("|")
+            ))?);
         }
+        for pattern in patterns {
+            all_patterns.push(Regex::new(pattern)?);
+        }
+
+        let exclusions = std::iter::once(DEFAULT_EXCLUSION)
+            .chain(exclusions.iter().map(|e| e.as_str()))
+            .map(|exclusion| Regex::new(&format!("(?i)^(?:{})$", exclusion)))
+            .collect::<Result<Vec<Regex>, regex::Error>>()?;
+
+        Ok(Self {
+            patterns: all_patterns,
+            exclusions,
+        })
     }
+
+    fn is_excluded(&self, key: &str) -> bool {
+        self.exclusions
+            .iter()
+            .any(|exclusion| exclusion.is_match(key))
+    }
+}
+
+/// Whether the match is not glued to a letter or digit
+///
+/// Underscores separate words in branch names, e.g. `feature/FOO-123_add-login`.
+fn has_boundaries(text: &str, start: usize, end: usize) -> bool {
+    !text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric)
+        && !text[end..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric)
 }
 
-impl TicketExtractionService for FooTicketExtractionService {
-    fn regex(&self) -> &Regex {
-        &self.regex
+impl TicketExtractionService for TicketExtractionServiceImpl {
+    fn extract_tickets(&self, text: &str) -> Vec<String> {
+        // (position, key), so that keys of different patterns keep the order of the text
+        let mut found: Vec<(usize, String)> = vec![];
+        for pattern in &self.patterns {
+            for captures in pattern.captures_iter(text) {
+                let whole = captures.get(0).unwrap();
+                if !has_boundaries(text, whole.start(), whole.end()) {
+                    continue;
+                }
+                let key = captures.get(1).unwrap_or(whole);
+                found.push((key.start(), key.as_str().to_uppercase()));
+            }
+        }
+        found.sort_by_key(|(position, _)| *position);
+
+        let mut tickets: Vec<String> = vec![];
+        for (_, key) in found {
+            if !self.is_excluded(&key) && !tickets.contains(&key) {
+                tickets.push(key);
+            }
+        }
+
+        tickets
     }
 }
 
-impl BarTicketExtractionService {
+/// Extracts GitHub issue references: `#123`, `owner/repo#123`, `Fixes #123`
+///
+/// The `(#123)` suffix GitHub appends to the subject of squash-merged commits is the PR, not an
+/// issue, so it is skipped.
+pub struct GithubIssueTicketExtractionService {
+    regex: Regex,
+    squash_merge_regex: Regex,
+}
+
+impl GithubIssueTicketExtractionService {
     pub fn new() -> Self {
         Self {
-            regex: Regex::new(r"[bB][aA][rR]-\d{1,6}").unwrap(),
+            regex: Regex::new(r"(?:\b[\w.-]+/[\w.-]+)?#\d+\b").unwrap(),
+            squash_merge_regex: Regex::new(r"\s*\(#\d+\)\s*$").unwrap(),
+        }
+    }
+}
+
+impl TicketExtractionService for GithubIssueTicketExtractionService {
+    fn extract_tickets(&self, text: &str) -> Vec<String> {
+        let subject_end = text.find('\n').unwrap_or(text.len());
+        let text = match self.squash_merge_regex.find(&text[..subject_end]) {
+            Some(m) => format!("{}{}", &text[..m.start()], &text[subject_end..]),
+            None => text.to_string(),
+        };
+
+        let mut tickets: Vec<String> = vec![];
+        for m in self.regex.find_iter(&text) {
+            let ticket = m.as_str().to_string();
+            if !tickets.contains(&ticket) {
+                tickets.push(ticket);
+            }
         }
+
+        tickets
     }
 }
 
-impl TicketExtractionService for BarTicketExtractionService {
-    fn regex(&self) -> &Regex {
-        &self.regex
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_extract_tickets_by_prefix() {
+        let service =
+            TicketExtractionServiceImpl::new(&strings(&["FOO", "BAR"]), &[], &[]).unwrap();
+
+        assert_eq!(
+            service.extract_tickets(
+                "foo-12: fix xfoo-13 and FOO-1234567, see Bar-7 (FOO-12, FOO-0, FOO-000)"
+            ),
+            vec!["FOO-12", "BAR-7"]
+        );
+    }
+
+    #[test]
+    fn test_extract_tickets_separated_by_underscores() {
+        let service = TicketExtractionServiceImpl::new(&strings(&["FOO"]), &[], &[]).unwrap();
+
+        assert_eq!(
+            service.extract_tickets("feature/FOO-123_add-login"),
+            vec!["FOO-123"]
+        );
+        assert_eq!(
+            service.extract_tickets("FOO-7_fix and bugfix_FOO-8"),
+            vec!["FOO-7", "FOO-8"]
+        );
+        assert!(service.extract_tickets("FOO-9a xFOO-10").is_empty());
+    }
+
+    #[test]
+    fn test_extract_tickets_with_patterns_and_exclusions() {
+        let service = TicketExtractionServiceImpl::new(
+            &strings(&["FOO"]),
+            &strings(&[r"ticket/(\d+)"]),
+            &strings(&["FOO-123"]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            service.extract_tickets("ticket/42 FOO-123 FOO-7 ticket/42"),
+            vec!["42", "FOO-7"]
+        );
+    }
+
+    #[test]
+    fn test_commit_trailers() {
+        assert_eq!(
+            commit_trailers("Fix login\n\nDetails: not a trailer block\nat all\n\nJira: FOO-1\nRefs: FOO-2, FOO-3\n"),
+            vec![("Jira", "FOO-1"), ("Refs", "FOO-2, FOO-3")]
+        );
+        assert!(commit_trailers("Jira: FOO-1").is_empty());
+        assert!(commit_trailers("Fix login\n\nSee FOO-1\nRefs: FOO-2").is_empty());
+    }
+
+    #[test]
+    fn test_ticket_source_text() {
+        let commit = GetCommitResult {
+            full_message: "Fix login\n\nSigned-off-by: Dev <dev@test.com>\nJira: FOO-1".to_string(),
+            pr_head_branch: "feature/FOO-2-login".to_string(),
+            ..Default::default()
+        };
+        let service = TicketExtractionServiceImpl::new(&strings(&["FOO"]), &[], &[]).unwrap();
+
+        assert_eq!(
+            service.extract_tickets(&TicketSource::Trailers.text(&commit)),
+            vec!["FOO-1"]
+        );
+        assert_eq!(
+            service.extract_tickets(&TicketSource::Branch.text(&commit)),
+            vec!["FOO-2"]
+        );
+    }
+
+    #[test]
+    fn test_extract_github_issues() {
+        let service = GithubIssueTicketExtractionService::new();
+
+        assert_eq!(
+            service.extract_tickets("Fixes #12, see acme/lib.rs#3 and color #12ab (#42) #12"),
+            vec!["#12", "acme/lib.rs#3", "#42"]
+        );
+    }
+
+    #[test]
+    fn test_extract_github_issues_skips_squash_merge_pr() {
+        let service = GithubIssueTicketExtractionService::new();
+
+        assert!(service.extract_tickets("Add login (#42)").is_empty());
+        assert_eq!(
+            service.extract_tickets("Add login (#42)\n\nCloses #12 (#13)"),
+            vec!["#12", "#13"]
+        );
+        assert_eq!(service.extract_tickets("Fix #12 (#42)"), vec!["#12"]);
     }
 }
@@ -0,0 +1,216 @@
+use crate::api::errors::{YouTrackBaseUrlUndefined, YouTrackTokenUndefined};
+use crate::api::issue_tracker_service::{IssueInfo, IssueTrackerService};
+use async_trait::async_trait;
+use std::error::Error;
+
+/// Custom field holding the workflow state of an issue
+const STATE_FIELD: &str = "State";
+
+/// Custom field holding the assignee of an issue
+const ASSIGNEE_FIELD: &str = "Assignee";
+
+/// Fields needed to build `IssueInfo`, YouTrack returns only the entity ids unless asked
+const FIELDS: &str = "idReadable,summary,customFields(name,value(name))";
+
+/// `IssueTrackerService` backed by the YouTrack REST API
+pub struct YoutrackServiceImpl {
+    pub base_url: String,
+    pub token: String,
+    /// Custom field deciding readiness
+    pub ready_field: String,
+    /// Values of `ready_field` that count as ready
+    pub ready_values: Vec<String>,
+}
+
+impl YoutrackServiceImpl {
+    pub fn new(
+        base_url: Option<&str>,
+        token: Option<&str>,
+        ready_field: Option<&str>,
+        ready_values: Vec<String>,
+    ) -> Result<Self, Box<dyn Error>> {
+        match (base_url, token) {
+            (None, _) => Err(Box::new(YouTrackBaseUrlUndefined)),
+            (_, None) => Err(Box::new(YouTrackTokenUndefined)),
+            (Some(base_url), Some(token)) => Ok(Self {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                token: token.to_string(),
+                ready_field: ready_field.unwrap_or(STATE_FIELD).to_string(),
+                ready_values: if ready_values.is_empty() {
+                    vec![
+                        "Done".to_string(),
+                        "Fixed".to_string(),
+                        "Verified".to_string(),
+                    ]
+                } else {
+                    ready_values
+                },
+            }),
+        }
+    }
+
+    /// Name of the value of a single or multi value custom field
+    fn custom_field_values(issue: &serde_json::Value, field: &str) -> Vec<String> {
+        let value = issue["customFields"]
+            .as_array()
+            .and_then(|fields| fields.iter().find(|f| f["name"].as_str() == Some(field)))
+            .map(|f| f["value"].clone())
+            .unwrap_or_default();
+
+        let values = match value {
+            serde_json::Value::Array(values) => values,
+            value => vec![value],
+        };
+
+        values
+            .iter()
+            .filter_map(|value| value["name"].as_str())
+            .map(|name| name.to_string())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl IssueTrackerService for YoutrackServiceImpl {
+    async fn get_issues(&self, issue_keys: Vec<String>) -> Result<Vec<IssueInfo>, String> {
+        if issue_keys.is_empty() {
+            return Err("No issue keys provided".to_string());
+        }
+
+        let query = format!("issue id: {}", issue_keys.join(", "));
+        let top = issue_keys.len().to_string();
+        let response = reqwest::Client::new()
+            .get(format!("{}/api/issues", self.base_url))
+            .header("Accept", "application/json")
+            .header("Authorization", format!("Bearer {}", self.token))
+            .query(&[
+                ("query", query.as_str()),
+                ("fields", FIELDS),
+                ("$top", top.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            eprintln!(
+                "Error fetching YouTrack issues: {:?}",
+                response.text().await.unwrap_or_default()
+            );
+            return Err("Error fetching YouTrack issues".to_string());
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        let issues = json
+            .as_array()
+            .ok_or("Error fetching YouTrack issues".to_string())?;
+
+        Ok(issues
+            .iter()
+            .map(|issue| IssueInfo {
+                key: issue["idReadable"].as_str().unwrap_or_default().to_string(),
+                summary: issue["summary"].as_str().unwrap_or_default().to_string(),
+                assignee: Self::custom_field_values(issue, ASSIGNEE_FIELD)
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+                issue_type: Self::custom_field_values(issue, "Type")
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+                priority: Self::custom_field_values(issue, "Priority")
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+                fix_versions: Self::custom_field_values(issue, "Fix versions"),
+                status: Self::custom_field_values(issue, STATE_FIELD)
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+                ready: Self::custom_field_values(issue, &self.ready_field)
+                    .iter()
+                    .any(|value| {
+                        self.ready_values
+                            .iter()
+                            .any(|ready_value| ready_value.eq_ignore_ascii_case(value))
+                    }),
+                ..Default::default()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_get_issues() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api/issues")
+            .match_header("Authorization", "Bearer perm:token")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("query".into(), "issue id: APP-1, APP-2".into()),
+                mockito::Matcher::UrlEncoded("fields".into(), FIELDS.into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!([
+                    {
+                        "idReadable": "APP-1",
+                        "customFields": [
+                            { "name": "State", "value": { "name": "Fixed" } },
+                            { "name": "Assignee", "value": null }
+                        ]
+                    },
+                    {
+                        "idReadable": "APP-2",
+                        "customFields": [{ "name": "State", "value": { "name": "In Progress" } }]
+                    }
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let service =
+            YoutrackServiceImpl::new(Some(&server.url()), Some("perm:token"), None, vec![])
+                .unwrap();
+        let issues = service
+            .get_issues(vec!["APP-1".to_string(), "APP-2".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].key, "APP-1");
+        assert_eq!(issues[0].status, "Fixed");
+        assert!(issues[0].ready);
+        assert_eq!(issues[1].status, "In Progress");
+        assert!(!issues[1].ready);
+    }
+
+    #[test]
+    fn test_custom_ready_field() {
+        let service = YoutrackServiceImpl::new(
+            Some("https://youtrack.example.com/"),
+            Some("token"),
+            Some("Release"),
+            vec!["Go".to_string()],
+        )
+        .unwrap();
+        let issue = json!({
+            "customFields": [
+                { "name": "State", "value": { "name": "Done" } },
+                { "name": "Release", "value": [{ "name": "Go" }] }
+            ]
+        });
+
+        assert_eq!(service.base_url, "https://youtrack.example.com");
+        assert_eq!(
+            YoutrackServiceImpl::custom_field_values(&issue, &service.ready_field),
+            vec!["Go".to_string()]
+        );
+    }
+}
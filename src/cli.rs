@@ -1,3 +1,10 @@
+use crate::api::metrics_service::MetricsFormat;
+use crate::api::release_gate_service::UntrackedPolicy;
+use crate::api::result_printer_service::ReportFormat;
+use crate::api::ticket_extraction_service::TicketSource;
+use crate::config::{IssueTracker, SourceControl};
+use crate::domain::to_deploy::services::info_gathering_service::GroupBy;
+use chrono::NaiveDate;
 use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
@@ -11,9 +18,46 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     ToDeploy(CommandToDeployArgs),
+    /// Render the release notes of the commits not deployed to an env yet
+    ReleaseNotes(CommandReleaseNotesArgs),
+    /// Move the tickets that went live with a deployment to their deployed status
+    MarkDeployed(CommandMarkDeployedArgs),
+    /// Tag the deployed commit and create a GitHub release of the changes since the previous one
+    Release(CommandReleaseArgs),
+    /// Open a PR against the config repository deploying a commit to an env
+    Promote(CommandPromoteArgs),
+    /// List the deployments to an env recorded in the config repository
+    History(CommandHistoryArgs),
+    /// Show which envs a ticket or commit is deployed to
+    Where(CommandWhereArgs),
+    /// Compute lead time, deployment frequency, batch size and change failure rate
+    Metrics(CommandMetricsArgs),
     Doctor(CommandDoctorArgs),
 }
 
+/// Where the project's commits and tickets are read from, shared by the project commands
+#[derive(Args)]
+pub struct BackendArgs {
+    /// Read commits from a local clone of the service repository instead of the API. PR details,
+    /// issues, releases, promotions and the config repository are still read from the API
+    #[arg(long)]
+    pub local_repo: Option<String>,
+
+    /// With --local-repo, report the commits without PR details instead of fetching them from the
+    /// API. The config repository is still read from the API
+    #[arg(long, requires = "local_repo")]
+    pub local_repo_only: bool,
+
+    /// Where the service and config repositories are hosted (overrides SOURCE_CONTROL and
+    /// SOURCE_CONTROL_<PROJECT>)
+    #[arg(long, value_enum)]
+    pub source_control: Option<SourceControl>,
+
+    /// Where the project's tickets are tracked (overrides ISSUE_TRACKER and ISSUE_TRACKER_<PROJECT>)
+    #[arg(long, value_enum)]
+    pub issue_tracker: Option<IssueTracker>,
+}
+
 #[derive(Args)]
 pub struct CommandToDeployArgs {
     #[arg(short, long)]
@@ -33,6 +77,244 @@ pub struct CommandToDeployArgs {
 
     #[arg(short, long)]
     pub deploy_version: Option<String>,
+
+    /// Path to a JSON file with release gating rules (overrides RELEASE_RULES_FILE)
+    #[arg(long)]
+    pub rules: Option<String>,
+
+    /// Follow only the first parent of merge commits, skipping the commits of merged branches
+    #[arg(long)]
+    pub first_parent: bool,
+
+    /// Group the commits, `--group-by pr` shows one entry per PR and `--group-by ticket` one per ticket
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+
+    /// Comma separated precedence of the places to look for ticket keys (overrides TICKET_SOURCES)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub ticket_sources: Option<Vec<TicketSource>>,
+
+    /// What to do with commits that do not reference any ticket (overrides UNTRACKED_POLICY)
+    #[arg(long, value_enum)]
+    pub untracked: Option<UntrackedPolicy>,
+
+    /// Built-in report template (overrides REPORT_FORMAT)
+    #[arg(long, value_enum)]
+    pub format: Option<ReportFormat>,
+
+    /// Path to a minijinja report template (overrides REPORT_TEMPLATE and --format)
+    #[arg(long)]
+    pub template: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CommandReleaseNotesArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    /// Environment the changes are not deployed to yet
+    #[arg(short, long)]
+    pub env: String,
+
+    /// Only include the changes already deployed to this environment, e.g. `staging` for `-e prod`
+    #[arg(long)]
+    pub to_env: Option<String>,
+
+    /// Path to a minijinja template (overrides RELEASE_NOTES_TEMPLATE_<PROJECT>)
+    #[arg(long)]
+    pub template: Option<String>,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandMarkDeployedArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    #[arg(short, long)]
+    pub env: String,
+
+    /// SHA deployed before this deployment, the previous deployment of the config repo history by
+    /// default
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// SHA of this deployment, the SHA currently deployed to the env by default
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Status to move the tickets to (overrides JIRA_DEPLOYED_STATUS_<ENV>)
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Print the planned transitions without moving any ticket
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Comment on every ticket with the env, build, SHA and time of the deployment
+    #[arg(long)]
+    pub comment: bool,
+
+    /// Build of the deployment to mention in the comment, e.g. the CI build number
+    #[arg(long)]
+    pub build: Option<String>,
+
+    /// Add the tickets to this fix version, creating it if needed
+    #[arg(long)]
+    pub fix_version: Option<String>,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandReleaseArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    #[arg(short, long)]
+    pub env: String,
+
+    /// Tag to create on the deployed commit, e.g. `v1.2.0`
+    #[arg(long)]
+    pub tag: String,
+
+    /// Name of the release, the tag by default
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// SHA of the previous release, the commit of the latest GitHub release by default
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// SHA to release, the SHA currently deployed to the env by default
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Print the planned tag and release notes without creating them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandPromoteArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    #[arg(short, long)]
+    pub env: String,
+
+    /// SHA to deploy
+    #[arg(long)]
+    pub to: String,
+
+    /// Group the commits of the PR description, see `to-deploy --group-by`
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+
+    /// Built-in template of the PR description, `markdown` by default
+    #[arg(long, value_enum)]
+    pub format: Option<ReportFormat>,
+
+    /// Path to a minijinja template of the PR description (overrides --format)
+    #[arg(long)]
+    pub template: Option<String>,
+
+    /// Print the planned config change and PR description without opening the PR
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Open the PR even if the release is on hold
+    #[arg(long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandHistoryArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    #[arg(short, long)]
+    pub env: String,
+
+    /// How many of the newest deployments to list
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandWhereArgs {
+    /// Ticket key, e.g. `FOO-123`, or commit SHA
+    pub target: String,
+
+    #[arg(short, long)]
+    pub owner: String,
+
+    #[arg(short, long)]
+    pub project: String,
+
+    /// Comma separated environments to check, every environment of the config file by default
+    #[arg(long, value_delimiter = ',')]
+    pub envs: Option<Vec<String>>,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
+}
+
+#[derive(Args)]
+pub struct CommandMetricsArgs {
+    #[arg(short, long)]
+    pub owner: String,
+
+    /// Comma separated projects, one row of metrics each
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    pub project: Vec<String>,
+
+    #[arg(short, long)]
+    pub env: String,
+
+    /// First day of the range, e.g. `2026-09-01`, 30 days before `--until` by default
+    #[arg(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Last day of the range, today by default
+    #[arg(long)]
+    pub until: Option<NaiveDate>,
+
+    #[arg(long, value_enum, default_value_t = MetricsFormat::Text)]
+    pub format: MetricsFormat,
+
+    #[command(flatten)]
+    pub backend: BackendArgs,
 }
 
 #[derive(Args)]
@@ -1,41 +1,603 @@
+use crate::api::release_gate_service::UntrackedPolicy;
+use crate::api::result_printer_service::ReportFormat;
+use crate::api::ticket_extraction_service::TicketSource;
+use anyhow::{anyhow, Context};
+use clap::ValueEnum;
+use std::collections::HashMap;
 use std::env;
 
-#[derive(Clone)]
+/// Where the service repositories and the config repository are hosted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SourceControl {
+    #[default]
+    Github,
+    Gitlab,
+    /// Bitbucket Server / Data Center
+    Bitbucket,
+}
+
+/// Where the tickets referenced by commits are tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum IssueTracker {
+    #[default]
+    Jira,
+    Linear,
+    Youtrack,
+    /// Issues of the source control repository, referenced as `#N` or `owner/repo#N`
+    GithubIssues,
+}
+
+/// How deppy authenticates against JIRA
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum JiraAuthMode {
+    /// Personal access token (JIRA Server / Data Center)
+    #[default]
+    Bearer,
+    /// Email and API token (JIRA Cloud)
+    Basic,
+    /// OAuth 2.0 access token
+    Oauth,
+}
+
+/// Which JIRA search endpoint is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum JiraSearchApi {
+    /// `/rest/api/2/search` (JIRA Server / Data Center)
+    #[default]
+    V2,
+    /// `/rest/api/3/search/jql` with token-based pagination (JIRA Cloud)
+    V3,
+}
+
+/// JIRA settings of a project that override the global ones, e.g. `JIRA_SERVER_<PROJECT>`
+#[derive(Debug, Clone, Default)]
+pub struct ProjectJiraConfig {
+    pub server: Option<String>,
+    pub token: Option<String>,
+    pub auth_mode: Option<JiraAuthMode>,
+    pub email: Option<String>,
+    pub search_api: Option<JiraSearchApi>,
+    pub ready_field: Option<String>,
+    pub ready_value: Option<String>,
+    pub epic_field: Option<String>,
+}
+
+/// Settings that `ProjectJiraConfig` reads per project, suffixed with `_<PROJECT>`
+const PROJECT_JIRA_SETTINGS: [&str; 8] = [
+    "JIRA_SERVER",
+    "JIRA_TOKEN",
+    "JIRA_AUTH",
+    "JIRA_EMAIL",
+    "JIRA_SEARCH_API",
+    "JIRA_READY_FIELD",
+    "JIRA_READY_VALUE",
+    "JIRA_EPIC_FIELD",
+];
+
+#[derive(Clone, Default)]
 pub struct Config {
     pub github_token: String,
     pub github_server: String,
     pub jira_token: String,
     pub jira_server: String,
-    pub versions_live: String,
+    pub jira_auth_mode: JiraAuthMode,
+    /// Account email, required by the basic auth mode
+    pub jira_email: Option<String>,
+    pub jira_search_api: JiraSearchApi,
+    /// Custom field deciding whether a JIRA ticket is ready, `customfield_19899` if unset
+    pub jira_ready_field: Option<String>,
+    /// Value of the readiness field that counts as ready, `Go` if unset
+    pub jira_ready_value: Option<String>,
+    /// Epic link custom field, e.g. `customfield_10014`, the parent epic is used if unset
+    pub jira_epic_field: Option<String>,
+    /// Additional JIRA fields to fetch
+    pub jira_extra_fields: Vec<String>,
+    /// Status that deployed tickets are moved to per environment, read from `JIRA_DEPLOYED_STATUS_<ENV>`
+    pub jira_deployed_statuses: HashMap<String, String>,
+    /// JIRA settings per project, read from `JIRA_SERVER_<PROJECT>`, `JIRA_TOKEN_<PROJECT>`, etc.
+    pub project_jira_configs: HashMap<String, ProjectJiraConfig>,
+    pub release_rules_file: Option<String>,
+    /// Path to a local clone of the service repository, commits are read from it instead of the API
+    pub local_repo: Option<String>,
+    /// Do not fetch PR details of the local repository commits from the API
+    pub local_repo_only: bool,
+    /// Source control of the projects without an entry in `project_source_controls`
+    pub source_control: SourceControl,
+    /// Source control per project, read from `SOURCE_CONTROL_<PROJECT>`
+    pub project_source_controls: HashMap<String, SourceControl>,
+    pub gitlab_token: Option<String>,
+    pub gitlab_server: Option<String>,
+    pub bitbucket_token: Option<String>,
+    pub bitbucket_server: Option<String>,
+    /// Issue tracker of the projects without an entry in `project_issue_trackers`
+    pub issue_tracker: IssueTracker,
+    /// Issue tracker per project, read from `ISSUE_TRACKER_<PROJECT>`
+    pub project_issue_trackers: HashMap<String, IssueTracker>,
+    pub linear_token: Option<String>,
+    pub linear_server: Option<String>,
+    /// Linear workflow states that count as ready, states of type `completed` if empty
+    pub linear_ready_states: Vec<String>,
+    pub youtrack_token: Option<String>,
+    pub youtrack_server: Option<String>,
+    /// YouTrack custom field deciding readiness, `State` if unset
+    pub youtrack_ready_field: Option<String>,
+    /// Values of the YouTrack readiness field that count as ready, `Done`, `Fixed` and `Verified` if empty
+    pub youtrack_ready_values: Vec<String>,
+    /// `owner/repo` that `#N` issue references point to, the service repository if unset
+    pub github_issues_repo: Option<String>,
+    /// Labels marking an issue as ready
+    pub github_issues_ready_labels: Vec<String>,
+    /// Milestones whose issues are ready, closed issues are ready if neither labels nor milestones are set
+    pub github_issues_ready_milestones: Vec<String>,
+    /// Ticket key prefixes per project, read from `TICKET_PREFIXES_<PROJECT>`
+    pub ticket_prefixes: HashMap<String, Vec<String>>,
+    /// Custom ticket key regexes, for keys that do not look like `PREFIX-123`
+    pub ticket_patterns: Vec<String>,
+    /// Regexes of ticket keys to ignore, e.g. placeholders of PR templates
+    pub ticket_exclusions: Vec<String>,
+    /// Precedence of the parts of a commit that ticket keys are extracted from
+    pub ticket_sources: Vec<TicketSource>,
+    /// What to do with commits that do not reference any ticket
+    pub untracked_policy: UntrackedPolicy,
+    /// Conventional commit types that do not need a ticket, e.g. `chore`
+    pub untracked_allowed_types: Vec<String>,
+    /// Parts of the author emails of commits that do not need a ticket, e.g. `dependabot`
+    pub untracked_allowed_authors: Vec<String>,
+    /// How many days of deployed commits `--group-by ticket` searches for partially deployed tickets
+    pub ticket_lookback_days: i64,
+    /// Release notes template file per project, read from `RELEASE_NOTES_TEMPLATE_<PROJECT>`
+    pub release_notes_templates: HashMap<String, String>,
+    /// Built-in template of the undeployed commits report
+    pub report_format: ReportFormat,
+    /// Template file of the undeployed commits report, overrides `report_format`
+    pub report_template: Option<String>,
 }
 
 impl Config {
-    pub fn new() -> Self
+    pub fn new() -> anyhow::Result<Self>
     where
         Self: Sized,
     {
-        let (_, github_token) = env::vars()
-            .find(|(key, _)| key == "GITHUB_TOKEN")
-            .expect("GITHUB_TOKEN not found");
-        let (_, github_server) = env::vars()
-            .find(|(key, _)| key == "GITHUB_SERVER")
-            .expect("failed to get github server");
-        let (_, jira_token) = env::vars()
-            .find(|(key, _)| key == "JIRA_TOKEN")
-            .expect("failed to get jira token");
-        let (_, jira_server) = env::vars()
-            .find(|(key, _)| key == "JIRA_SERVER")
-            .expect("failed to get jira server");
-        let (_, versions_live) = env::vars()
-            .find(|(key, _)| key == "VERSIONS_URL_LIVE")
-            .expect("failed to get versions live server");
-
-        Config {
+        let github_token = env::var("GITHUB_TOKEN").context("GITHUB_TOKEN not found")?;
+        let github_server = env::var("GITHUB_SERVER").context("failed to get github server")?;
+        let jira_token = env::var("JIRA_TOKEN").context("failed to get jira token")?;
+        let jira_server = env::var("JIRA_SERVER").context("failed to get jira server")?;
+        let jira_auth_mode = enum_var("JIRA_AUTH")?;
+        let jira_email = optional_var("JIRA_EMAIL");
+        let jira_search_api = enum_var("JIRA_SEARCH_API")?;
+        let release_rules_file = optional_var("RELEASE_RULES_FILE");
+        let source_control = enum_var("SOURCE_CONTROL")?;
+        let project_source_controls = env::vars()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, value)| {
+                let project = key.strip_prefix("SOURCE_CONTROL_")?;
+                Some(parse_enum(&key, &value).map(|value| (project.to_string(), value)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let gitlab_token = optional_var("GITLAB_TOKEN");
+        let gitlab_server = optional_var("GITLAB_SERVER");
+        let bitbucket_token = optional_var("BITBUCKET_TOKEN");
+        let bitbucket_server = optional_var("BITBUCKET_SERVER");
+        let jira_ready_field = optional_var("JIRA_READY_FIELD");
+        let jira_ready_value = optional_var("JIRA_READY_VALUE");
+        let jira_epic_field = optional_var("JIRA_EPIC_FIELD");
+        let jira_extra_fields = list_var("JIRA_EXTRA_FIELDS");
+        let jira_deployed_statuses = env::vars()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, value)| {
+                let env = key.strip_prefix("JIRA_DEPLOYED_STATUS_")?;
+                Some((env.to_string(), value))
+            })
+            .collect();
+        let mut project_jira_configs: HashMap<String, ProjectJiraConfig> = HashMap::new();
+        for (key, value) in env::vars().filter(|(_, value)| !value.is_empty()) {
+            let (setting, project) = match PROJECT_JIRA_SETTINGS.iter().find_map(|setting| {
+                let project = key.strip_prefix(setting)?.strip_prefix('_')?;
+                Some((*setting, project))
+            }) {
+                Some(setting_and_project) => setting_and_project,
+                None => continue,
+            };
+            let project_jira_config = project_jira_configs.entry(project.to_string()).or_default();
+            match setting {
+                "JIRA_SERVER" => project_jira_config.server = Some(value),
+                "JIRA_TOKEN" => project_jira_config.token = Some(value),
+                "JIRA_AUTH" => project_jira_config.auth_mode = Some(parse_enum(&key, &value)?),
+                "JIRA_EMAIL" => project_jira_config.email = Some(value),
+                "JIRA_SEARCH_API" => {
+                    project_jira_config.search_api = Some(parse_enum(&key, &value)?)
+                }
+                "JIRA_READY_FIELD" => project_jira_config.ready_field = Some(value),
+                "JIRA_READY_VALUE" => project_jira_config.ready_value = Some(value),
+                "JIRA_EPIC_FIELD" => project_jira_config.epic_field = Some(value),
+                _ => {}
+            }
+        }
+        let issue_tracker = enum_var("ISSUE_TRACKER")?;
+        let project_issue_trackers = env::vars()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, value)| {
+                let project = key.strip_prefix("ISSUE_TRACKER_")?;
+                Some(parse_enum(&key, &value).map(|value| (project.to_string(), value)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let linear_token = optional_var("LINEAR_TOKEN");
+        let linear_server = optional_var("LINEAR_SERVER");
+        let linear_ready_states = list_var("LINEAR_READY_STATES");
+        let youtrack_token = optional_var("YOUTRACK_TOKEN");
+        let youtrack_server = optional_var("YOUTRACK_SERVER");
+        let youtrack_ready_field = optional_var("YOUTRACK_READY_FIELD");
+        let youtrack_ready_values = list_var("YOUTRACK_READY_VALUES");
+        let github_issues_repo = optional_var("GITHUB_ISSUES_REPO");
+        let github_issues_ready_labels = list_var("GITHUB_ISSUES_READY_LABELS");
+        let github_issues_ready_milestones = list_var("GITHUB_ISSUES_READY_MILESTONES");
+        let ticket_prefixes = env::vars()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, _)| {
+                let project = key.strip_prefix("TICKET_PREFIXES_")?;
+                Some((project.to_string(), list_var(&key)))
+            })
+            .collect();
+        // Regexes may contain commas, e.g. `\d{1,6}`
+        let ticket_patterns = list_var_separated_by("TICKET_PATTERNS", ';');
+        let ticket_exclusions = list_var_separated_by("TICKET_EXCLUSIONS", ';');
+        let ticket_sources = list_var("TICKET_SOURCES")
+            .iter()
+            .map(|source| parse_enum("TICKET_SOURCES", source))
+            .collect::<anyhow::Result<_>>()?;
+        let untracked_policy = enum_var("UNTRACKED_POLICY")?;
+        let untracked_allowed_types = list_var("UNTRACKED_ALLOWED_TYPES");
+        let untracked_allowed_authors = list_var("UNTRACKED_ALLOWED_AUTHORS");
+        let ticket_lookback_days = env::var("TICKET_LOOKBACK_DAYS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(14);
+        let release_notes_templates = env::vars()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, value)| {
+                let project = key.strip_prefix("RELEASE_NOTES_TEMPLATE_")?;
+                Some((project.to_string(), value))
+            })
+            .collect();
+        let report_format = enum_var("REPORT_FORMAT")?;
+        let report_template = optional_var("REPORT_TEMPLATE");
+
+        Ok(Config {
             github_token,
             github_server,
             jira_token,
             jira_server,
-            versions_live,
+            jira_auth_mode,
+            jira_email,
+            jira_search_api,
+            jira_ready_field,
+            jira_ready_value,
+            jira_epic_field,
+            jira_extra_fields,
+            jira_deployed_statuses,
+            project_jira_configs,
+            release_rules_file,
+            local_repo: None,
+            local_repo_only: false,
+            source_control,
+            project_source_controls,
+            gitlab_token,
+            gitlab_server,
+            bitbucket_token,
+            bitbucket_server,
+            issue_tracker,
+            project_issue_trackers,
+            linear_token,
+            linear_server,
+            linear_ready_states,
+            youtrack_token,
+            youtrack_server,
+            youtrack_ready_field,
+            youtrack_ready_values,
+            github_issues_repo,
+            github_issues_ready_labels,
+            github_issues_ready_milestones,
+            ticket_prefixes,
+            ticket_patterns,
+            ticket_exclusions,
+            ticket_sources,
+            untracked_policy,
+            untracked_allowed_types,
+            untracked_allowed_authors,
+            ticket_lookback_days,
+            release_notes_templates,
+            report_format,
+            report_template,
+        })
+    }
+
+    /// Source control of a project, e.g. `SOURCE_CONTROL_FOO_WEB` for `foo-web`
+    pub fn source_control(&self, project: &str) -> SourceControl {
+        self.project_source_controls
+            .get(&Self::project_key(project))
+            .copied()
+            .unwrap_or(self.source_control)
+    }
+
+    /// Override the source control of a project
+    pub fn set_source_control(&mut self, project: &str, source_control: SourceControl) {
+        self.project_source_controls
+            .insert(Self::project_key(project), source_control);
+    }
+
+    /// Issue tracker of a project, e.g. `ISSUE_TRACKER_FOO_WEB` for `foo-web`
+    pub fn issue_tracker(&self, project: &str) -> IssueTracker {
+        self.project_issue_trackers
+            .get(&Self::project_key(project))
+            .copied()
+            .unwrap_or(self.issue_tracker)
+    }
+
+    /// Override the issue tracker of a project
+    pub fn set_issue_tracker(&mut self, project: &str, issue_tracker: IssueTracker) {
+        self.project_issue_trackers
+            .insert(Self::project_key(project), issue_tracker);
+    }
+
+    /// Ticket key prefixes of a project, e.g. `TICKET_PREFIXES_FOO_WEB` for `foo-web`
+    pub fn ticket_prefixes(&self, project: &str) -> Option<&Vec<String>> {
+        self.ticket_prefixes.get(&Self::project_key(project))
+    }
+
+    /// Release notes template of a project, e.g. `RELEASE_NOTES_TEMPLATE_FOO_WEB` for `foo-web`
+    pub fn release_notes_template(&self, project: &str) -> Option<&String> {
+        self.release_notes_templates
+            .get(&Self::project_key(project))
+    }
+
+    /// Override the release notes template of a project
+    pub fn set_release_notes_template(&mut self, project: &str, path: &str) {
+        self.release_notes_templates
+            .insert(Self::project_key(project), path.to_string());
+    }
+
+    /// JIRA settings overridden for a project, e.g. `JIRA_SERVER_FOO_WEB` for `foo-web`
+    pub fn project_jira_config(&self, project: &str) -> ProjectJiraConfig {
+        self.project_jira_configs
+            .get(&Self::project_key(project))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Status that tickets deployed to an environment are moved to, e.g. `JIRA_DEPLOYED_STATUS_PROD` for `prod`
+    pub fn jira_deployed_status(&self, env: &str) -> Option<&String> {
+        self.jira_deployed_statuses.get(&Self::project_key(env))
+    }
+
+    fn project_key(project: &str) -> String {
+        project.to_uppercase().replace('-', "_")
+    }
+
+    /// Web URL of a repository on the source control of the project
+    fn repo_url(&self, owner: &str, repo: &str) -> String {
+        match self.source_control(repo) {
+            SourceControl::Github => format!("{}/{}/{}", self.github_server, owner, repo),
+            SourceControl::Gitlab => format!(
+                "{}/{}/{}",
+                self.gitlab_server.as_deref().unwrap_or_default(),
+                owner,
+                repo
+            ),
+            SourceControl::Bitbucket => format!(
+                "{}/projects/{}/repos/{}",
+                self.bitbucket_server.as_deref().unwrap_or_default(),
+                owner,
+                repo
+            ),
         }
     }
+
+    /// Web URL of a commit on the source control of the project
+    pub fn commit_url(&self, owner: &str, repo: &str, commit_sha: &str) -> String {
+        let repo_url = self.repo_url(owner, repo);
+        match self.source_control(repo) {
+            SourceControl::Github => format!("{}/commit/{}", repo_url, commit_sha),
+            SourceControl::Gitlab => format!("{}/-/commit/{}", repo_url, commit_sha),
+            SourceControl::Bitbucket => format!("{}/commits/{}", repo_url, commit_sha),
+        }
+    }
+
+    /// Web URL comparing two commits on the source control of the project
+    pub fn compare_url(&self, owner: &str, repo: &str, from: &str, to: &str) -> String {
+        let repo_url = self.repo_url(owner, repo);
+        match self.source_control(repo) {
+            SourceControl::Github => format!("{}/compare/{}...{}", repo_url, from, to),
+            SourceControl::Gitlab => format!("{}/-/compare/{}...{}", repo_url, from, to),
+            SourceControl::Bitbucket => format!(
+                "{}/compare/commits?sourceBranch={}&targetBranch={}",
+                repo_url, to, from
+            ),
+        }
+    }
+}
+
+/// Comma separated list from an environment variable, empty if unset
+fn list_var(key: &str) -> Vec<String> {
+    list_var_separated_by(key, ',')
+}
+
+/// Value of an environment variable, `None` if unset or empty as in `.env.example`
+fn optional_var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Value of an enum from an environment variable, the default if unset
+fn enum_var<T: ValueEnum + Default>(key: &str) -> anyhow::Result<T> {
+    match optional_var(key) {
+        Some(value) => parse_enum(key, &value),
+        None => Ok(T::default()),
+    }
+}
+
+/// Case-insensitive value of an enum, the error lists the valid values
+fn parse_enum<T: ValueEnum>(key: &str, value: &str) -> anyhow::Result<T> {
+    T::from_str(value.trim(), true).map_err(|_| {
+        let valid_values: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|variant| variant.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        anyhow!(
+            "invalid {} `{}`, expected one of: {}",
+            key,
+            value,
+            valid_values.join(", ")
+        )
+    })
+}
+
+fn list_var_separated_by(key: &str, separator: char) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_default()
+        .split(separator)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_urls() {
+        let github = Config {
+            github_server: "https://github.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            github.commit_url("jrumjantsev", "foo", "123"),
+            "https://github.com/jrumjantsev/foo/commit/123"
+        );
+        assert_eq!(
+            github.compare_url("jrumjantsev", "foo", "123", "456"),
+            "https://github.com/jrumjantsev/foo/compare/123...456"
+        );
+
+        let gitlab = Config {
+            source_control: SourceControl::Gitlab,
+            gitlab_server: Some("https://gitlab.com".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            gitlab.commit_url("jrumjantsev", "foo", "123"),
+            "https://gitlab.com/jrumjantsev/foo/-/commit/123"
+        );
+        assert_eq!(
+            gitlab.compare_url("jrumjantsev", "foo", "123", "456"),
+            "https://gitlab.com/jrumjantsev/foo/-/compare/123...456"
+        );
+
+        let bitbucket = Config {
+            source_control: SourceControl::Bitbucket,
+            bitbucket_server: Some("https://bitbucket.example.com".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            bitbucket.commit_url("FOO", "foo", "123"),
+            "https://bitbucket.example.com/projects/FOO/repos/foo/commits/123"
+        );
+        assert_eq!(
+            bitbucket.compare_url("FOO", "foo", "123", "456"),
+            "https://bitbucket.example.com/projects/FOO/repos/foo/compare/commits?sourceBranch=456&targetBranch=123"
+        );
+    }
+
+    #[test]
+    fn test_issue_tracker_per_project() {
+        let mut config = Config {
+            issue_tracker: IssueTracker::Linear,
+            ..Default::default()
+        };
+        config.set_issue_tracker("bar-web", IssueTracker::Youtrack);
+
+        assert_eq!(config.issue_tracker("foo-web"), IssueTracker::Linear);
+        assert_eq!(config.issue_tracker("bar-web"), IssueTracker::Youtrack);
+        assert_eq!(
+            config.project_issue_trackers["BAR_WEB"],
+            IssueTracker::Youtrack
+        );
+    }
+
+    #[test]
+    fn test_source_control_per_project() {
+        let mut config = Config {
+            github_server: "https://github.com".to_string(),
+            gitlab_server: Some("https://gitlab.com".to_string()),
+            ..Default::default()
+        };
+        config.set_source_control("bar-web", SourceControl::Gitlab);
+
+        assert_eq!(config.source_control("foo-web"), SourceControl::Github);
+        assert_eq!(config.source_control("bar-web"), SourceControl::Gitlab);
+        assert_eq!(
+            config.commit_url("jrumjantsev", "foo-web", "123"),
+            "https://github.com/jrumjantsev/foo-web/commit/123"
+        );
+        assert_eq!(
+            config.commit_url("jrumjantsev", "bar-web", "123"),
+            "https://gitlab.com/jrumjantsev/bar-web/-/commit/123"
+        );
+    }
+
+    #[test]
+    fn test_parse_enum() {
+        assert_eq!(
+            parse_enum::<SourceControl>("SOURCE_CONTROL", "GitLab").unwrap(),
+            SourceControl::Gitlab
+        );
+        assert_eq!(
+            parse_enum::<SourceControl>("SOURCE_CONTROL_FOO_WEB", "gihtub")
+                .unwrap_err()
+                .to_string(),
+            "invalid SOURCE_CONTROL_FOO_WEB `gihtub`, expected one of: github, gitlab, bitbucket"
+        );
+        assert_eq!(
+            parse_enum::<IssueTracker>("ISSUE_TRACKER", "GitHub-Issues").unwrap(),
+            IssueTracker::GithubIssues
+        );
+        assert_eq!(
+            parse_enum::<JiraSearchApi>("JIRA_SEARCH_API", "v3").unwrap(),
+            JiraSearchApi::V3
+        );
+        assert_eq!(
+            parse_enum::<JiraAuthMode>("JIRA_AUTH", "basci")
+                .unwrap_err()
+                .to_string(),
+            "invalid JIRA_AUTH `basci`, expected one of: bearer, basic, oauth"
+        );
+        assert_eq!(
+            parse_enum::<TicketSource>("TICKET_SOURCES", "title")
+                .unwrap_err()
+                .to_string(),
+            "invalid TICKET_SOURCES `title`, expected one of: trailers, message, pr-title, pr-body, branch"
+        );
+        assert_eq!(
+            parse_enum::<UntrackedPolicy>("UNTRACKED_POLICY", "blok")
+                .unwrap_err()
+                .to_string(),
+            "invalid UNTRACKED_POLICY `blok`, expected one of: allow, warn, block"
+        );
+        assert_eq!(
+            parse_enum::<ReportFormat>("REPORT_FORMAT", "Markdown").unwrap(),
+            ReportFormat::Markdown
+        );
+    }
+
+    #[test]
+    fn test_optional_var_treats_empty_as_unset() {
+        env::set_var("DEPPY_TEST_REPORT_TEMPLATE", "");
+        assert_eq!(optional_var("DEPPY_TEST_REPORT_TEMPLATE"), None);
+
+        env::set_var("DEPPY_TEST_REPORT_TEMPLATE", "report.j2");
+        assert_eq!(
+            optional_var("DEPPY_TEST_REPORT_TEMPLATE"),
+            Some("report.j2".to_string())
+        );
+    }
 }
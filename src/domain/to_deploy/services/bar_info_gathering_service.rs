@@ -1,67 +1,73 @@
-use crate::api::config_extraction_service::{
-    ConfigExtractionService, GithubConfigExtractionServiceImpl,
-};
-use crate::api::github_service::{GithubService, GithubServiceImpl};
-use crate::api::jira_service::{JiraService, JiraServiceImpl};
-use crate::api::result_printer_service::{ResultPrinterService, ResultPrinterServiceImpl};
-use crate::api::ticket_extraction_service::{BarTicketExtractionService, TicketExtractionService};
+use crate::api::config_extraction_service::ConfigExtractionService;
+use crate::api::github_service::GithubService;
+use crate::api::issue_tracker_service::IssueTrackerService;
+use crate::api::metrics_service::MetricsService;
+use crate::api::release_gate_service::ReleaseGateService;
+use crate::api::release_notes_service::ReleaseNotesService;
+use crate::api::result_printer_service::ResultPrinterService;
+use crate::api::revert_detection_service::RevertDetectionService;
+use crate::api::ticket_extraction_service::TicketExtractionService;
 use crate::config::Config;
+use crate::constants;
 use crate::domain::to_deploy::services::info_gathering_service::InfoGatheringService;
+use crate::domain::to_deploy::services::project_services::ProjectServices;
 use async_trait::async_trait;
 
 pub struct BarInfoGatheringService<'a> {
-    github_service: Box<dyn GithubService + 'a>,
-    ticket_extraction_service: Box<dyn TicketExtractionService + 'a>,
-    result_printer_service: Box<dyn ResultPrinterService + 'a>,
-    jira_service: Box<dyn JiraService + 'a>,
-    config_extraction_service: Box<dyn ConfigExtractionService + 'a>,
+    services: ProjectServices<'a>,
 }
 
 impl<'a> BarInfoGatheringService<'a> {
-    pub fn new(config: &'a Config) -> Self
+    pub fn new(owner_name: &str, config: &'a Config) -> anyhow::Result<Self>
     where
         Self: Sized,
     {
-        let github_service = GithubServiceImpl::new(
-            Some(&config.github_server.as_str()),
-            Some(&config.github_token.as_str()),
-        )
-        .unwrap();
-
-        BarInfoGatheringService {
-            github_service: Box::new(github_service.clone()),
-            ticket_extraction_service: Box::new(BarTicketExtractionService::new()),
-            result_printer_service: Box::new(ResultPrinterServiceImpl::new(config.clone())),
-            jira_service: Box::new(JiraServiceImpl::new(
-                config.jira_server.to_string(),
-                config.jira_token.to_string(),
-            )),
-            config_extraction_service: Box::new(GithubConfigExtractionServiceImpl::new(Box::new(
-                github_service.clone(),
-            ))),
-        }
+        Ok(BarInfoGatheringService {
+            services: ProjectServices::new(
+                owner_name,
+                constants::PROJECT_BAR_WEB,
+                &["BAR"],
+                config,
+            )?,
+        })
     }
 }
 
 #[async_trait]
 impl<'a> InfoGatheringService<'a> for BarInfoGatheringService<'a> {
     fn get_github_service(&self) -> &dyn GithubService {
-        self.github_service.as_ref()
+        self.services.github_service.as_ref()
     }
 
     fn get_result_printer_service(&self) -> &dyn ResultPrinterService {
-        self.result_printer_service.as_ref()
+        self.services.result_printer_service.as_ref()
     }
 
     fn get_ticket_extraction_service(&self) -> &dyn TicketExtractionService {
-        self.ticket_extraction_service.as_ref()
+        self.services.ticket_extraction_service.as_ref()
     }
 
-    fn get_jira_service(&self) -> &dyn JiraService {
-        self.jira_service.as_ref()
+    fn get_issue_tracker_service(&self) -> &dyn IssueTrackerService {
+        self.services.issue_tracker_service.as_ref()
     }
 
     fn get_config_extraction_service(&self) -> &dyn ConfigExtractionService {
-        self.config_extraction_service.as_ref()
+        self.services.config_extraction_service.as_ref()
+    }
+
+    fn get_release_gate_service(&self) -> &dyn ReleaseGateService {
+        self.services.release_gate_service.as_ref()
+    }
+
+    fn get_revert_detection_service(&self) -> &dyn RevertDetectionService {
+        self.services.revert_detection_service.as_ref()
+    }
+
+    fn get_release_notes_service(&self) -> &dyn ReleaseNotesService {
+        self.services.release_notes_service.as_ref()
+    }
+
+    fn get_metrics_service(&self) -> &dyn MetricsService {
+        self.services.metrics_service.as_ref()
     }
 }
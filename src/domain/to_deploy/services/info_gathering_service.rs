@@ -1,14 +1,23 @@
-use crate::api::config_extraction_service::ConfigExtractionService;
+use crate::api::config_extraction_service::{ConfigExtractionService, Deployment};
 use crate::api::github_service::{GetCommitResult, GithubService};
-use crate::api::jira_service::{JiraService, JiraTicketInfo};
+use crate::api::issue_tracker_service::{IssueInfo, IssueTrackerService};
+use crate::api::metrics_service::{DeploymentMetrics, DeploymentSample, MetricsService};
+use crate::api::release_gate_service::{ReleaseGateService, UntrackedPolicy, Verdict};
+use crate::api::release_notes_service::{
+    ReleaseNoteCategory, ReleaseNoteEntry, ReleaseNoteSection, ReleaseNotes, ReleaseNotesService,
+};
 use crate::api::result_printer_service::{
-    PrintMessageFromExtractTicketsResultPayload, ResultPrinterService, TicketInfo,
+    PrintMessageFromExtractTicketsResultPayload, ResultPrinterService, TicketCategory, TicketInfo,
+    TicketInfoGroup,
 };
-use crate::api::ticket_extraction_service::TicketExtractionService;
+use crate::api::revert_detection_service::RevertDetectionService;
+use crate::api::ticket_extraction_service::{TicketExtractionService, TicketSource};
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use futures::future::try_join_all;
 use futures::stream::iter;
 use futures::StreamExt;
+use regex::Regex;
 use std::collections::HashMap;
 
 #[async_trait]
@@ -16,15 +25,20 @@ pub trait InfoGatheringService<'a>: Sync + Send {
     fn get_github_service(&self) -> &dyn GithubService;
     fn get_result_printer_service(&self) -> &dyn ResultPrinterService;
     fn get_ticket_extraction_service(&self) -> &dyn TicketExtractionService;
-    fn get_jira_service(&self) -> &dyn JiraService;
+    fn get_issue_tracker_service(&self) -> &dyn IssueTrackerService;
     fn get_config_extraction_service(&self) -> &dyn ConfigExtractionService;
+    fn get_release_gate_service(&self) -> &dyn ReleaseGateService;
+    fn get_revert_detection_service(&self) -> &dyn RevertDetectionService;
+    fn get_release_notes_service(&self) -> &dyn ReleaseNotesService;
+    fn get_metrics_service(&self) -> &dyn MetricsService;
 
     async fn show_undeployed_commits(
         &self,
         owner_name: &str,
         service_name: &str,
         env: &str,
-    ) -> anyhow::Result<Vec<String>> {
+        options: &ShowUndeployedCommitsOptions,
+    ) -> anyhow::Result<ShowUndeployedCommitsResult> {
         /*
            Part 1. Extract the commit sha for the service
         */
@@ -46,11 +60,34 @@ pub trait InfoGatheringService<'a>: Sync + Send {
            Part 3. Get a list of commits since the date of the latest commit
         */
         let date_time = get_commit_result.date_time;
-        let commit_shas = &self
+        let commits_since = self
             .get_github_service()
             .get_commits_since(owner_name, service_name, date_time)
-            .await?
-            .commit_shas;
+            .await?;
+        let shas_to_release = match &options.to_sha {
+            Some(to_sha) => {
+                let shas_to_release = commits_since
+                    .shas_between(&source_sha, to_sha)
+                    .unwrap_or_default();
+                if shas_to_release.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Commit {} not found after the deployed commit {}",
+                        to_sha,
+                        short_sha(&source_sha)
+                    ));
+                }
+                Some(shas_to_release)
+            }
+            None => None,
+        };
+        let mut commit_shas = if options.first_parent {
+            commits_since.first_parent_shas()
+        } else {
+            commits_since.commit_shas
+        };
+        if let Some(shas_to_release) = &shas_to_release {
+            commit_shas.retain(|sha| shas_to_release.contains(sha));
+        }
 
         /*
            Part 4. Collect additional information for each and every commit
@@ -60,6 +97,10 @@ pub trait InfoGatheringService<'a>: Sync + Send {
                 .get_commit(owner_name, service_name, sha, true)
         });
         let all_commits = try_join_all(all_commits_futures).await?;
+        let all_commits = match options.group_by {
+            Some(GroupBy::Pr) => group_commits_by_pr(all_commits),
+            Some(GroupBy::Ticket) | None => all_commits,
+        };
 
         /*
            Part 5. Working with JIRA tickets
@@ -68,36 +109,41 @@ pub trait InfoGatheringService<'a>: Sync + Send {
         */
         // TODO: abstract away from here
         // key - JIRA ticket key
-        let issue_keys: HashMap<String, Vec<JiraTicketInfo>> = iter(&all_commits)
+        let ticket_sources: &[TicketSource] = if options.ticket_sources.is_empty() {
+            &TicketSource::DEFAULT_PRECEDENCE
+        } else {
+            &options.ticket_sources
+        };
+        let issue_keys: HashMap<String, Vec<IssueInfo>> = iter(&all_commits)
             .then(|commit: &GetCommitResult| async move {
-                for field in vec![&commit.full_message, &commit.pr_title, &commit.pr_body] {
-                    let tickets = self.get_ticket_extraction_service().extract_tickets(field);
+                for source in ticket_sources {
+                    let tickets = self
+                        .get_ticket_extraction_service()
+                        .extract_tickets(&source.text(commit));
                     if tickets.is_empty() {
                         continue;
                     }
 
-                    let jira_tickets: Result<Vec<JiraTicketInfo>, String> = self
-                        .get_jira_service()
-                        .get_jira_issues(tickets.clone())
+                    let jira_tickets: Result<Vec<IssueInfo>, String> = self
+                        .get_issue_tracker_service()
+                        .get_issues(tickets.clone())
                         .await;
 
-                    if let Err(err) = jira_tickets {
-                        eprintln!("Error fetching JIRA issues: {:?}", err);
-                        continue;
+                    match jira_tickets {
+                        Err(err) => {
+                            eprintln!("Error fetching JIRA issues: {:?}", err);
+                            continue;
+                        }
+                        // None of the keys is a known ticket, try the next source
+                        Ok(jira_tickets) if jira_tickets.is_empty() => continue,
+                        Ok(jira_tickets) => return Ok((commit.sha.to_string(), jira_tickets)),
                     }
-
-                    return Ok((commit.sha.to_string(), jira_tickets.unwrap()));
                 }
 
                 Ok((commit.sha.to_string(), vec![])) // No tickets found in any field
             })
             .filter_map(
-                |result: Result<(String, Vec<JiraTicketInfo>), String>| async move {
-                    match result {
-                        Ok(value) => Some(value),
-                        Err(_) => None,
-                    }
-                },
+                |result: Result<(String, Vec<IssueInfo>), String>| async move { result.ok() },
             )
             .collect()
             .await;
@@ -105,37 +151,102 @@ pub trait InfoGatheringService<'a>: Sync + Send {
         /*
          Part 6. Group everything by commit sha
         */
-        let mut commit_jira_tickets: HashMap<String, JiraTicketShort> = HashMap::new();
-        for (commit_sha, jira_tickets) in issue_keys.iter() {
-            for ticket in jira_tickets {
-                commit_jira_tickets.insert(
-                    commit_sha.clone(),
-                    JiraTicketShort {
-                        ticket_key: ticket.key.clone(),
-                        ticket_ready: ticket.ready.clone(),
-                    },
-                );
-            }
-        }
+        // Commits reverted within the undeployed range cancel each other out
+        let revert_pairs = self
+            .get_revert_detection_service()
+            .find_revert_pairs(&all_commits);
 
         let mut ticket_infos: Vec<TicketInfo> = vec![];
         for commit in all_commits.iter() {
-            let jira_info = commit_jira_tickets
-                .get(&commit.sha)
-                .cloned()
-                .unwrap_or_default();
+            let (jira_info, ticket_ready) = commit_ticket(
+                issue_keys
+                    .get(&commit.sha)
+                    .map(|jira_tickets| jira_tickets.as_slice())
+                    .unwrap_or_default(),
+            );
+
+            let references_ticket = ticket_sources.iter().any(|source| {
+                !self
+                    .get_ticket_extraction_service()
+                    .extract_tickets(&source.text(commit))
+                    .is_empty()
+            });
+            let ticket_category = if references_ticket {
+                TicketCategory::Tracked
+            } else if self
+                .get_release_gate_service()
+                .is_exempt_from_ticket(&commit.full_message, &commit.author_email)
+            {
+                TicketCategory::Exempt
+            } else {
+                TicketCategory::Untracked
+            };
 
-            ticket_infos.push(TicketInfo {
+            let mut ticket_info = TicketInfo {
                 commit_sha: commit.sha.as_str(),
                 author_email: commit.author_email.as_str(),
                 commit_message: commit.pr_title.as_str(),
-                ticket_key: jira_info.ticket_key.clone(),
-                ticket_ready: jira_info.ticket_ready.clone(),
-            });
+                ticket_key: jira_info.key.clone(),
+                ticket_ready,
+                ticket_status: jira_info.status.clone(),
+                ticket_details: jira_info,
+                ticket_category,
+                pr_number: commit.pr_number,
+                pr_url: commit.pr_url.as_str(),
+                pr_labels: commit.pr_labels.as_slice(),
+                pr_merged_by: commit.pr_merged_by.as_str(),
+                pr_reviewers: commit.pr_reviewers.as_slice(),
+                verdict: Verdict::Ship,
+                reverts: revert_pairs
+                    .iter()
+                    .find(|pair| pair.revert_sha == commit.sha)
+                    .map(|pair| pair.reverted_sha.as_str()),
+                reverted_by: revert_pairs
+                    .iter()
+                    .find(|pair| pair.reverted_sha == commit.sha)
+                    .map(|pair| pair.revert_sha.as_str()),
+            };
+            if !ticket_info.is_in_revert_pair() {
+                ticket_info.verdict = self
+                    .get_release_gate_service()
+                    .evaluate_commit(&ticket_info);
+            }
+            ticket_infos.push(ticket_info);
         }
 
         /*
-           Part 7. Print the result
+           Part 7. Evaluate the release gating rules
+        */
+        let verdicts: Vec<Verdict> = ticket_infos.iter().map(|info| info.verdict).collect();
+        let release_verdict = self.get_release_gate_service().evaluate_release(&verdicts);
+        let safe_to_deploy_sha = find_safe_to_deploy_sha(
+            &ticket_infos,
+            self.get_release_gate_service().untracked_policy(),
+        )
+        .map(|sha| sha.to_string());
+
+        let ticket_groups = match options.group_by {
+            Some(GroupBy::Ticket) => {
+                let deployed_commits = self
+                    .find_deployed_commits_of_tickets(
+                        owner_name,
+                        service_name,
+                        &get_commit_result,
+                        &commit_shas,
+                        options,
+                    )
+                    .await?;
+                Some(group_ticket_infos_by_ticket(
+                    &ticket_infos,
+                    &issue_keys,
+                    &deployed_commits,
+                ))
+            }
+            _ => None,
+        };
+
+        /*
+           Part 8. Print the result
         */
         let output = self
             .get_result_printer_service()
@@ -144,18 +255,1457 @@ pub trait InfoGatheringService<'a>: Sync + Send {
                     owner: owner_name,
                     repo: service_name,
                     ticket_infos,
-                    issue_keys,
                     last_commit_in_production: get_commit_result.sha,
-                    commit_sha_to_release: None,
+                    commit_sha_to_release: shas_to_release
+                        .as_ref()
+                        .and_then(|shas| shas.first().cloned()),
+                    release_verdict,
+                    safe_to_deploy_sha,
+                    ticket_groups,
                 },
-            );
+            )?;
+
+        Ok(ShowUndeployedCommitsResult {
+            output,
+            release_verdict,
+        })
+    }
+
+    /// Find the deployed commits of every ticket, looking `options.ticket_lookback_days` back
+    /// from the deployed commit
+    ///
+    /// Returns the SHAs of the deployed commits by ticket key. Only the commit messages are
+    /// searched for ticket keys, PR details would cost several requests per commit.
+    async fn find_deployed_commits_of_tickets(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        deployed_commit: &GetCommitResult,
+        undeployed_commit_shas: &[String],
+        options: &ShowUndeployedCommitsOptions,
+    ) -> anyhow::Result<HashMap<String, Vec<String>>> {
+        let since = deployed_commit.date_time
+            - Duration::try_days(options.ticket_lookback_days).unwrap_or_default();
+        let deployed_commit_shas: Vec<String> = self
+            .get_github_service()
+            .get_commits_since(owner_name, service_name, since)
+            .await?
+            .commit_shas
+            .into_iter()
+            .filter(|sha| !undeployed_commit_shas.contains(sha))
+            .collect();
+
+        let deployed_commits = try_join_all(deployed_commit_shas.iter().map(|sha| {
+            self.get_github_service()
+                .get_commit(owner_name, service_name, sha, false)
+        }))
+        .await?;
+
+        let mut deployed_commits_by_ticket: HashMap<String, Vec<String>> = HashMap::new();
+        for commit in deployed_commits {
+            let tickets = self
+                .get_ticket_extraction_service()
+                .extract_tickets(&commit.full_message);
+            for ticket in tickets {
+                deployed_commits_by_ticket
+                    .entry(ticket)
+                    .or_default()
+                    .push(commit.sha.clone());
+            }
+        }
+
+        Ok(deployed_commits_by_ticket)
+    }
+
+    /// Get the commits after `from_sha` up to `to_sha` (the newest commit if unset) with their PR
+    /// details, newest first
+    async fn get_commits_between(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        from_sha: &str,
+        to_sha: Option<&str>,
+    ) -> anyhow::Result<Vec<GetCommitResult>> {
+        let from_commit = self
+            .get_github_service()
+            .get_commit(owner_name, service_name, from_sha, false)
+            .await?;
+        let commits_since = self
+            .get_github_service()
+            .get_commits_since(owner_name, service_name, from_commit.date_time)
+            .await?;
+        let to_sha = match to_sha {
+            Some(to_sha) => to_sha.to_string(),
+            None => commits_since
+                .commit_shas
+                .first()
+                .cloned()
+                .unwrap_or_default(),
+        };
+        let commit_shas = match commits_since.shas_between(from_sha, &to_sha) {
+            Ok(commit_shas) => commit_shas,
+            Err(error) => {
+                // A rollback deploys a commit older than the previous one, it ships no commits
+                let to_commit = self
+                    .get_github_service()
+                    .get_commit(owner_name, service_name, &to_sha, false)
+                    .await?;
+                if to_commit.date_time < from_commit.date_time {
+                    return Ok(vec![]);
+                }
+                return Err(error);
+            }
+        };
+
+        try_join_all(commit_shas.iter().map(|sha| {
+            self.get_github_service()
+                .get_commit(owner_name, service_name, sha, true)
+        }))
+        .await
+    }
+
+    /// Ticket keys of the first source of the commit that has any,
+    /// `TicketSource::DEFAULT_PRECEDENCE` is used if no sources are given
+    fn extract_ticket_keys(
+        &self,
+        commit: &GetCommitResult,
+        ticket_sources: &[TicketSource],
+    ) -> Vec<String> {
+        let ticket_sources: &[TicketSource] = if ticket_sources.is_empty() {
+            &TicketSource::DEFAULT_PRECEDENCE
+        } else {
+            ticket_sources
+        };
+
+        ticket_sources
+            .iter()
+            .map(|source| {
+                self.get_ticket_extraction_service()
+                    .extract_tickets(&source.text(commit))
+            })
+            .find(|tickets| !tickets.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Render the release notes of the commits not deployed to `options.env` yet, or of the
+    /// commits deployed to `options.to_env` but not to `options.env`
+    ///
+    /// Commits of the same ticket, or else of the same PR, make a single entry. Commits reverted
+    /// within the range are left out.
+    async fn release_notes(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        options: &ReleaseNotesOptions,
+    ) -> anyhow::Result<String> {
+        let from_sha = self
+            .get_config_extraction_service()
+            .extract_commit_sha(service_name, &options.env)
+            .await?
+            .commit_sha;
+        let to_sha = match &options.to_env {
+            Some(to_env) => Some(
+                self.get_config_extraction_service()
+                    .extract_commit_sha(service_name, to_env)
+                    .await?
+                    .commit_sha,
+            ),
+            None => None,
+        };
+
+        self.render_release_notes(
+            owner_name,
+            service_name,
+            Some(&options.env),
+            &from_sha,
+            to_sha.as_deref(),
+            &options.ticket_sources,
+        )
+        .await
+    }
+
+    /// Render the release notes of the commits after `from_sha` up to `to_sha`
+    ///
+    /// The newest commit is used if `to_sha` is unset. Reverted commits and their reverts are left
+    /// out.
+    async fn render_release_notes(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: Option<&str>,
+        from_sha: &str,
+        to_sha: Option<&str>,
+        ticket_sources: &[TicketSource],
+    ) -> anyhow::Result<String> {
+        let commits = self
+            .get_commits_between(owner_name, service_name, from_sha, to_sha)
+            .await?;
+        let revert_pairs = self
+            .get_revert_detection_service()
+            .find_revert_pairs(&commits);
+        let commits: Vec<&GetCommitResult> = commits
+            .iter()
+            .filter(|commit| {
+                !revert_pairs
+                    .iter()
+                    .any(|pair| pair.revert_sha == commit.sha || pair.reverted_sha == commit.sha)
+            })
+            .collect();
+
+        let commit_ticket_keys: Vec<Vec<String>> = commits
+            .iter()
+            .map(|commit| self.extract_ticket_keys(commit, ticket_sources))
+            .collect();
+        let mut all_ticket_keys: Vec<String> = vec![];
+        for ticket_key in commit_ticket_keys.concat() {
+            if !all_ticket_keys.contains(&ticket_key) {
+                all_ticket_keys.push(ticket_key);
+            }
+        }
+        let issues: Vec<IssueInfo> = if all_ticket_keys.is_empty() {
+            vec![]
+        } else {
+            self.get_issue_tracker_service()
+                .get_issues(all_ticket_keys)
+                .await
+                .unwrap_or_else(|err| {
+                    eprintln!("Error fetching issues: {:?}", err);
+                    vec![]
+                })
+        };
+
+        // (entry key, category, entry), in the order of the commits
+        let mut entries: Vec<(String, ReleaseNoteCategory, ReleaseNoteEntry)> = vec![];
+        for (commit, ticket_keys) in commits.iter().zip(commit_ticket_keys) {
+            let issue = ticket_keys
+                .first()
+                .and_then(|key| issues.iter().find(|issue| &issue.key == key));
+            let entry_key = match (ticket_keys.first(), commit.pr_number) {
+                (Some(ticket_key), _) => format!("ticket:{}", ticket_key),
+                (None, Some(pr_number)) => format!("pr:{}", pr_number),
+                (None, None) => format!("commit:{}", commit.sha),
+            };
+            let category = ReleaseNoteCategory::of(commit, issue);
+
+            let position = entries.iter().position(|(key, _, _)| key == &entry_key);
+            let (_, entry_category, entry) = match position {
+                Some(position) => &mut entries[position],
+                None => {
+                    let title = match issue {
+                        Some(issue) if !issue.summary.is_empty() => issue.summary.clone(),
+                        _ if !commit.pr_title.is_empty() => commit.pr_title.clone(),
+                        _ => commit
+                            .full_message
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                    };
+                    entries.push((
+                        entry_key,
+                        category,
+                        ReleaseNoteEntry {
+                            title,
+                            ticket_key: ticket_keys.first().cloned().unwrap_or_default(),
+                            issue_type: issue.map(|i| i.issue_type.clone()).unwrap_or_default(),
+                            components: issue.map(|i| i.components.clone()).unwrap_or_default(),
+                            pr_number: commit.pr_number,
+                            pr_url: commit.pr_url.clone(),
+                            ..Default::default()
+                        },
+                    ));
+                    entries.last_mut().unwrap()
+                }
+            };
+
+            // The most significant category of the commits wins
+            *entry_category = (*entry_category).min(category);
+            entry.commit_shas.push(commit.sha.clone());
+            if !entry.authors.contains(&commit.author_email) {
+                entry.authors.push(commit.author_email.clone());
+            }
+            for label in &commit.pr_labels {
+                if !entry.pr_labels.contains(label) {
+                    entry.pr_labels.push(label.clone());
+                }
+            }
+        }
+
+        let sections = ReleaseNoteCategory::ALL
+            .iter()
+            .map(|category| ReleaseNoteSection {
+                title: category.title().to_string(),
+                entries: entries
+                    .iter()
+                    .filter(|(_, entry_category, _)| entry_category == category)
+                    .map(|(_, _, entry)| entry.clone())
+                    .collect(),
+            })
+            .filter(|section| !section.entries.is_empty())
+            .collect();
+
+        self.get_release_notes_service().render(&ReleaseNotes {
+            project: service_name.to_string(),
+            env: env.map(|env| env.to_string()),
+            from_sha: from_sha.to_string(),
+            to_sha: to_sha
+                .map(|sha| sha.to_string())
+                .or(commits.first().map(|commit| commit.sha.clone()))
+                .unwrap_or_default(),
+            sections,
+        })
+    }
+
+    /// Move the tickets that went live between two deployments to the deployed status of the env
+    ///
+    /// The tickets of the commits after `options.from_sha` up to the deployed commit (or
+    /// `options.to_sha`) are moved to `options.status`. Tickets already in that status are skipped.
+    async fn mark_deployed(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        options: &MarkDeployedOptions,
+    ) -> anyhow::Result<MarkDeployedResult> {
+        let to_sha = match &options.to_sha {
+            Some(to_sha) => to_sha.clone(),
+            None => {
+                self.get_config_extraction_service()
+                    .extract_commit_sha(service_name, env)
+                    .await?
+                    .commit_sha
+            }
+        };
+        let from_sha = match &options.from_sha {
+            Some(from_sha) => from_sha.clone(),
+            None => {
+                // The deployment history is newest first, the previous SHA follows the deployments
+                // of `to_sha`
+                let deployments = self
+                    .get_config_extraction_service()
+                    .deployment_history(service_name, env)
+                    .await?;
+                deployments
+                    .iter()
+                    .skip_while(|deployment| !deployment.commit_sha.starts_with(&to_sha))
+                    .find(|deployment| !deployment.commit_sha.starts_with(&to_sha))
+                    .map(|deployment| deployment.commit_sha.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "No deployment before {} in the {} history, pass the previous SHA",
+                            short_sha(&to_sha),
+                            env
+                        )
+                    })?
+            }
+        };
+
+        let commits = self
+            .get_commits_between(owner_name, service_name, &from_sha, Some(&to_sha))
+            .await?;
+
+        let mut ticket_keys: Vec<String> = vec![];
+        for commit in &commits {
+            for ticket in self.extract_ticket_keys(commit, &options.ticket_sources) {
+                if !ticket_keys.contains(&ticket) {
+                    ticket_keys.push(ticket);
+                }
+            }
+        }
+
+        let mut output = vec![format!(
+            "Tickets deployed to {} ({}..{}):",
+            env,
+            short_sha(&from_sha),
+            short_sha(&to_sha)
+        )];
+        let mut failed_tickets: Vec<String> = vec![];
+        if ticket_keys.is_empty() {
+            output.push("No tickets found".to_string());
+            return Ok(MarkDeployedResult {
+                output,
+                failed_tickets,
+            });
+        }
+
+        let issues = self
+            .get_issue_tracker_service()
+            .get_issues(ticket_keys)
+            .await
+            .map_err(|err| anyhow::anyhow!(err))?;
+        // Re-running for the same deployment finds the marker and does not comment twice
+        let comment_marker = deployment_comment_marker(env, options.build.as_deref(), &to_sha);
+        let comment = format!(
+            "{} at {}",
+            comment_marker,
+            Utc::now().format("%Y-%m-%d %H:%M UTC")
+        );
+        let tracker = self.get_issue_tracker_service();
+        for issue in issues {
+            let mut errors: Vec<String> = vec![];
+
+            if issue.status.eq_ignore_ascii_case(&options.status) {
+                output.push(format!("⏭️ [{}] already {}", issue.key, issue.status));
+            } else if options.dry_run {
+                output.push(format!(
+                    "📝 [{}] {} -> {} (dry run)",
+                    issue.key, issue.status, options.status
+                ));
+            } else {
+                match tracker.transition_issue(&issue.key, &options.status).await {
+                    Ok(()) => output.push(format!(
+                        "✅ [{}] {} -> {}",
+                        issue.key, issue.status, options.status
+                    )),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if options.comment {
+                if options.dry_run {
+                    output.push(format!("📝 [{}] comment: {} (dry run)", issue.key, comment));
+                } else {
+                    match tracker
+                        .add_comment_once(&issue.key, &comment, &comment_marker)
+                        .await
+                    {
+                        Ok(true) => output.push(format!("💬 [{}] {}", issue.key, comment)),
+                        Ok(false) => output.push(format!("⏭️ [{}] already commented", issue.key)),
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+
+            if let Some(fix_version) = &options.fix_version {
+                if issue.fix_versions.contains(fix_version) {
+                    output.push(format!(
+                        "⏭️ [{}] already in fix version {}",
+                        issue.key, fix_version
+                    ));
+                } else if options.dry_run {
+                    output.push(format!(
+                        "📝 [{}] fix version {} (dry run)",
+                        issue.key, fix_version
+                    ));
+                } else {
+                    match tracker.add_fix_version(&issue.key, fix_version).await {
+                        Ok(()) => {
+                            output.push(format!("🏷️ [{}] fix version {}", issue.key, fix_version))
+                        }
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+
+            if !errors.is_empty() {
+                output.extend(
+                    errors
+                        .iter()
+                        .map(|err| format!("❌ [{}] {}", issue.key, err)),
+                );
+                failed_tickets.push(issue.key);
+            }
+        }
+
+        Ok(MarkDeployedResult {
+            output,
+            failed_tickets,
+        })
+    }
+
+    /// Tag the deployed commit and create a GitHub release listing the changes since the previous
+    /// release
+    ///
+    /// Nothing is created if the tag already exists. The previous release is the latest release
+    /// of the repository unless `options.from_sha` is given.
+    async fn create_release(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        options: &CreateReleaseOptions,
+    ) -> anyhow::Result<Vec<String>> {
+        let github = self.get_github_service();
+        let to_sha = match &options.to_sha {
+            Some(to_sha) => to_sha.clone(),
+            None => {
+                self.get_config_extraction_service()
+                    .extract_commit_sha(service_name, env)
+                    .await?
+                    .commit_sha
+            }
+        };
+
+        // A previous run may have created the tag but failed to create the release
+        let tag_sha = github
+            .get_tag_commit_sha(owner_name, service_name, &options.tag)
+            .await?;
+        if tag_sha.is_some() {
+            if let Some(release_url) = github
+                .get_release_url(owner_name, service_name, &options.tag)
+                .await?
+            {
+                return Ok(vec![format!(
+                    "⏭️ Release {} already exists {}, skipping",
+                    options.tag, release_url
+                )]);
+            }
+        }
+        // The release describes the tagged commit, whatever is deployed now
+        let to_sha = tag_sha.clone().unwrap_or(to_sha);
+
+        let from_sha = match &options.from_sha {
+            Some(from_sha) => from_sha.clone(),
+            None => {
+                let previous_tag = github
+                    .get_latest_release_tag(owner_name, service_name)
+                    .await?
+                    .ok_or(anyhow::anyhow!(
+                        "No previous release of {}/{}, pass the previous release SHA with --from",
+                        owner_name,
+                        service_name
+                    ))?;
+                github
+                    .get_tag_commit_sha(owner_name, service_name, &previous_tag)
+                    .await?
+                    .ok_or(anyhow::anyhow!(
+                        "Tag {} of the previous release not found",
+                        previous_tag
+                    ))?
+            }
+        };
+
+        let name = options.name.clone().unwrap_or(options.tag.clone());
+        let body = self
+            .render_release_notes(
+                owner_name,
+                service_name,
+                Some(env),
+                &from_sha,
+                Some(&to_sha),
+                &options.ticket_sources,
+            )
+            .await?;
+
+        let mut output = vec![];
+        if options.dry_run {
+            output.push(match tag_sha {
+                Some(_) => format!(
+                    "⏭️ Tag {} already exists at {}",
+                    options.tag,
+                    short_sha(&to_sha)
+                ),
+                None => format!("📝 Tag {} at {} (dry run)", options.tag, short_sha(&to_sha)),
+            });
+            output.push(format!("📝 Release {} (dry run):", name));
+            output.push(String::new());
+            output.extend(body.lines().map(|line| line.to_string()));
+            return Ok(output);
+        }
+
+        match tag_sha {
+            Some(_) => output.push(format!(
+                "⏭️ Tag {} already exists at {}",
+                options.tag,
+                short_sha(&to_sha)
+            )),
+            None => {
+                github
+                    .create_tag(
+                        owner_name,
+                        service_name,
+                        &options.tag,
+                        &to_sha,
+                        &format!("Deployed to {}", env),
+                    )
+                    .await?;
+                output.push(format!("🏷️ Tag {} at {}", options.tag, short_sha(&to_sha)));
+            }
+        }
+        let release_url = github
+            .create_release(owner_name, service_name, &options.tag, &name, &body)
+            .await?;
+        output.push(format!("✅ Release {} {}", name, release_url));
+
+        Ok(output)
+    }
+
+    /// Deployments of the service to the env, newest first, with the commits each one shipped
+    ///
+    /// Only the `options.limit` newest deployments between `options.since` and `options.until`
+    /// are returned. The oldest deployment of the history has no commits, as nothing is known
+    /// about what was deployed before it.
+    async fn deployment_history(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        options: &HistoryOptions,
+    ) -> anyhow::Result<Vec<DeploymentRecord>> {
+        let deployments = self
+            .get_config_extraction_service()
+            .deployment_history(service_name, env)
+            .await?;
+
+        let mut records: Vec<DeploymentRecord> = vec![];
+        let in_range = deployments
+            .iter()
+            .enumerate()
+            .filter(|(_, deployment)| {
+                options
+                    .since
+                    .is_none_or(|since| deployment.date_time >= since)
+                    && options
+                        .until
+                        .is_none_or(|until| deployment.date_time < until)
+            })
+            .take(options.limit);
+        for (index, deployment) in in_range {
+            let previous = deployments.get(index + 1);
+            let commits = match previous {
+                Some(previous) => {
+                    self.get_commits_between(
+                        owner_name,
+                        service_name,
+                        &previous.commit_sha,
+                        Some(&deployment.commit_sha),
+                    )
+                    .await?
+                }
+                None => vec![],
+            };
+            let mut ticket_keys: Vec<String> = vec![];
+            for commit in &commits {
+                for ticket_key in self.extract_ticket_keys(commit, &options.ticket_sources) {
+                    if !ticket_keys.contains(&ticket_key) {
+                        ticket_keys.push(ticket_key);
+                    }
+                }
+            }
+
+            records.push(DeploymentRecord {
+                deployment: deployment.clone(),
+                previous_commit_sha: previous.map(|previous| previous.commit_sha.clone()),
+                commits,
+                ticket_keys,
+            });
+        }
+
+        Ok(records)
+    }
+
+    /// Lead time, deployment frequency, batch size and change failure rate of the deployments to
+    /// the env between `since` and `until`
+    ///
+    /// A deployment failed if one of its commits is reverted by a commit deployed since, even
+    /// after `until`. The lead time of a commit of a PR starts when the PR is merged.
+    async fn metrics(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> anyhow::Result<DeploymentMetrics> {
+        let records = self
+            .deployment_history(
+                owner_name,
+                service_name,
+                env,
+                &HistoryOptions {
+                    limit: usize::MAX,
+                    since: Some(since),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        // Reverts deployed after the range still mark the commits of the range as failed
+        let shipped_commits: Vec<GetCommitResult> = records
+            .iter()
+            .flat_map(|record| record.commits.clone())
+            .collect();
+        // A commit reverted later failed, even if the revert was reverted in turn
+        let revert_pairs = self
+            .get_revert_detection_service()
+            .find_direct_revert_pairs(&shipped_commits);
+        let samples: Vec<DeploymentSample> = records
+            .iter()
+            .filter(|record| record.deployment.date_time < until)
+            .map(|record| DeploymentSample {
+                date_time: record.deployment.date_time,
+                commit_dates: record
+                    .commits
+                    .iter()
+                    .map(|commit| commit.pr_merged_at.unwrap_or(commit.date_time))
+                    .collect(),
+                failed: record.commits.iter().any(|commit| {
+                    revert_pairs
+                        .iter()
+                        .any(|pair| pair.reverted_sha == commit.sha)
+                }),
+            })
+            .collect();
+
+        Ok(self
+            .get_metrics_service()
+            .compute(service_name, env, since, until, &samples))
+    }
+
+    /// Timeline of the deployments of the service to the env, newest first
+    async fn history(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        options: &HistoryOptions,
+    ) -> anyhow::Result<Vec<String>> {
+        let records = self
+            .deployment_history(owner_name, service_name, env, options)
+            .await?;
+
+        let mut output = vec![format!("Deployments of {} to {}:", service_name, env)];
+        if records.is_empty() {
+            output.push("No deployments found".to_string());
+        }
+        for record in records {
+            let deployment = &record.deployment;
+            let shipped = match (&record.previous_commit_sha, record.commits.len()) {
+                (None, _) => "first known deployment".to_string(),
+                (Some(_), 0) => "no new commits".to_string(),
+                (Some(_), 1) => "1 commit".to_string(),
+                (Some(_), count) => format!("{} commits", count),
+            };
+            let tickets = if record.ticket_keys.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", record.ticket_keys.join(", "))
+            };
+            output.push(format!(
+                "🚀 {} {} merged by {}, {}{}",
+                deployment.date_time.format("%Y-%m-%d %H:%M UTC"),
+                short_sha(&deployment.commit_sha),
+                deployment.merged_by,
+                shipped,
+                tickets
+            ));
+            output.extend(record.commits.iter().map(|commit| {
+                format!(
+                    "    {} {} ({})",
+                    short_sha(&commit.sha),
+                    commit.full_message.lines().next().unwrap_or_default(),
+                    commit.author_email
+                )
+            }));
+        }
+
+        Ok(output)
+    }
+
+    /// Whether a ticket or commit is deployed to each env, and since when
+    ///
+    /// The commits of a ticket are found by searching the commit messages, PR titles and PR
+    /// branches for its key. An env whose deployed SHA cannot be read is reported as unknown. The
+    /// time of the first deployment is looked up in the deployment history if it is available.
+    async fn where_deployed(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        target: &str,
+        options: &WhereOptions,
+    ) -> anyhow::Result<Vec<String>> {
+        let github = self.get_github_service();
+        let is_sha = Regex::new(r"^[0-9a-fA-F]{7,40}$").unwrap().is_match(target);
+        let commit_shas: Vec<String> = if is_sha {
+            vec![target.to_string()]
+        } else {
+            // Search matches loosely, e.g. `FOO-1` finds `FOO-12` too
+            let mentions_target = |text: &str| {
+                self.get_ticket_extraction_service()
+                    .extract_tickets(text)
+                    .iter()
+                    .any(|ticket| ticket.eq_ignore_ascii_case(target))
+            };
+            let (found_shas, found_pull_requests) = futures::try_join!(
+                github.search_commits(owner_name, service_name, target),
+                github.search_pull_requests(owner_name, service_name, target)
+            )?;
+            let found_commits = try_join_all(
+                found_shas
+                    .iter()
+                    .map(|sha| github.get_commit(owner_name, service_name, sha, false)),
+            )
+            .await?;
+            let mut commit_shas: Vec<String> = found_commits
+                .into_iter()
+                .filter(|commit| mentions_target(&commit.full_message))
+                .map(|commit| commit.sha)
+                .collect();
+            for pull_request in found_pull_requests {
+                let is_match = mentions_target(&pull_request.title)
+                    || mentions_target(&pull_request.head_branch);
+                if is_match && !commit_shas.contains(&pull_request.merge_commit_sha) {
+                    commit_shas.push(pull_request.merge_commit_sha);
+                }
+            }
+            commit_shas
+        };
+
+        let shas = commit_shas
+            .iter()
+            .map(|sha| short_sha(sha))
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let mut output = vec![match commit_shas.len() {
+            1 if is_sha => format!("{}:", short_sha(target)),
+            1 => format!("{} (1 commit: {}):", target, shas),
+            count => format!("{} ({} commits: {}):", target, count, shas),
+        }];
+        if commit_shas.is_empty() {
+            output.push(format!("No commits or PRs mention {}", target));
+            return Ok(output);
+        }
+
+        let envs = if options.envs.is_empty() {
+            self.get_config_extraction_service()
+                .list_envs(service_name)
+                .await?
+        } else {
+            options.envs.clone()
+        };
+        // Commits deployed to each SHA, out of `commit_shas`. Environments and their deployment
+        // histories share SHAs, so each SHA is compared with the commits only once
+        let mut deployed_counts: HashMap<String, usize> = HashMap::new();
+
+        for env in envs {
+            let deployed_sha = match self
+                .get_config_extraction_service()
+                .extract_commit_sha(service_name, &env)
+                .await
+            {
+                Ok(deployed) => deployed.commit_sha,
+                Err(err) => {
+                    output.push(format!("❔ {}: unknown ({:#})", env, err));
+                    continue;
+                }
+            };
+            let count = match deployed_count(
+                github,
+                owner_name,
+                service_name,
+                &commit_shas,
+                &deployed_sha,
+                &mut deployed_counts,
+            )
+            .await
+            {
+                Ok(count) => count,
+                Err(err) => {
+                    output.push(format!("❔ {}: unknown ({:#})", env, err));
+                    continue;
+                }
+            };
+            if count == 0 {
+                output.push(format!("❌ {}: not deployed", env));
+                continue;
+            }
+            if count < commit_shas.len() {
+                output.push(format!(
+                    "🟡 {}: partially deployed ({}/{} commits)",
+                    env,
+                    count,
+                    commit_shas.len()
+                ));
+                continue;
+            }
+
+            // The first deployment of the newest run of deployments that include every commit,
+            // so that a rollback and a redeployment count as deployed again
+            let deployments = self
+                .get_config_extraction_service()
+                .deployment_history(service_name, &env)
+                .await
+                .unwrap_or_default();
+            let mut first_deployment: Option<usize> = None;
+            for (index, deployment) in deployments.iter().enumerate() {
+                let count = deployed_count(
+                    github,
+                    owner_name,
+                    service_name,
+                    &commit_shas,
+                    &deployment.commit_sha,
+                    &mut deployed_counts,
+                )
+                .await?;
+                if count < commit_shas.len() {
+                    break;
+                }
+                first_deployment = Some(index);
+            }
+            let since = match first_deployment.map(|index| (index, &deployments[index])) {
+                // Nothing is known about the deployments before the oldest one
+                Some((index, deployment)) if index + 1 == deployments.len() => format!(
+                    ", deployed at or before {}",
+                    deployment.date_time.format("%Y-%m-%d %H:%M UTC")
+                ),
+                Some((_, deployment)) => format!(
+                    ", first deployed {} by {}",
+                    deployment.date_time.format("%Y-%m-%d %H:%M UTC"),
+                    deployment.merged_by
+                ),
+                None => String::new(),
+            };
+            output.push(format!("✅ {}: deployed{}", env, since));
+        }
 
         Ok(output)
     }
+
+    /// Open a PR against the config repository deploying `options.to_sha` to the env
+    ///
+    /// The description of the PR is the report of the commits after the deployed commit up to
+    /// `options.to_sha`.
+    async fn promote(
+        &self,
+        owner_name: &str,
+        service_name: &str,
+        env: &str,
+        options: &PromoteOptions,
+    ) -> anyhow::Result<Vec<String>> {
+        let promotion = self
+            .get_config_extraction_service()
+            .plan_promotion(service_name, env, &options.to_sha)
+            .await?;
+        if promotion.is_noop() {
+            return Ok(vec![format!(
+                "⏭️ {} is already deployed to {}",
+                promotion.to_image_tag, env
+            )]);
+        }
+
+        let report = self
+            .show_undeployed_commits(
+                owner_name,
+                service_name,
+                env,
+                &ShowUndeployedCommitsOptions {
+                    to_sha: Some(options.to_sha.clone()),
+                    ..options.report_options.clone()
+                },
+            )
+            .await?;
+        let title = format!(
+            "Promote {} to {}: {}",
+            service_name, env, promotion.to_image_tag
+        );
+        let body = report.output.join("\n");
+
+        let mut output = vec![];
+        if options.dry_run {
+            output.push(format!(
+                "📝 {}: {} -> {} (dry run)",
+                promotion.file_path, promotion.from_image_tag, promotion.to_image_tag
+            ));
+            output.push(format!("📝 PR {} (dry run):", title));
+            output.push(String::new());
+            output.extend(report.output);
+        } else if report.release_verdict == Verdict::Hold && !options.force {
+            return Err(anyhow::anyhow!(
+                "the release of {} is on hold, pass --force to open the PR anyway",
+                short_sha(&options.to_sha)
+            ));
+        } else {
+            let pull_request_url = self
+                .get_config_extraction_service()
+                .open_promotion_pr(&promotion, &title, &body)
+                .await?;
+            output.push(format!("✅ {} {}", title, pull_request_url));
+        }
+
+        Ok(output)
+    }
+}
+
+/// Deployment comment without its timestamp, e.g. `Deployed to prod in build 42 (sha abc1234)`
+pub fn deployment_comment_marker(env: &str, build: Option<&str>, sha: &str) -> String {
+    match build {
+        Some(build) => format!(
+            "Deployed to {} in build {} (sha {})",
+            env,
+            build,
+            short_sha(sha)
+        ),
+        None => format!("Deployed to {} (sha {})", env, short_sha(sha)),
+    }
+}
+
+/// Abbreviated SHA, as shown in the reports
+fn short_sha(sha: &str) -> &str {
+    &sha[..7.min(sha.len())]
 }
 
-#[derive(Clone, Default)]
-struct JiraTicketShort {
-    ticket_key: String,
-    ticket_ready: bool,
+/// Number of `commit_shas` that are ancestors of `deployed_sha`, cached in `deployed_counts`
+async fn deployed_count(
+    github: &dyn GithubService,
+    owner_name: &str,
+    repo_name: &str,
+    commit_shas: &[String],
+    deployed_sha: &str,
+    deployed_counts: &mut HashMap<String, usize>,
+) -> anyhow::Result<usize> {
+    if let Some(count) = deployed_counts.get(deployed_sha) {
+        return Ok(*count);
+    }
+
+    let is_deployed = try_join_all(
+        commit_shas
+            .iter()
+            .map(|sha| github.is_ancestor(owner_name, repo_name, sha, deployed_sha)),
+    )
+    .await?;
+    let count = is_deployed.into_iter().filter(|deployed| *deployed).count();
+    deployed_counts.insert(deployed_sha.to_string(), count);
+
+    Ok(count)
+}
+
+/// How to group the undeployed commits in the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// One entry per PR
+    Pr,
+    /// One entry per ticket, listing its commits
+    Ticket,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ShowUndeployedCommitsOptions {
+    /// Follow only the first parent of merge commits
+    pub first_parent: bool,
+    pub group_by: Option<GroupBy>,
+    /// Where to look for ticket keys, the first source with keys wins
+    /// `TicketSource::DEFAULT_PRECEDENCE` is used if empty
+    pub ticket_sources: Vec<TicketSource>,
+    /// How many days before the deployed commit to look for deployed commits of the same tickets,
+    /// used by `--group-by ticket` to find partially deployed tickets
+    pub ticket_lookback_days: i64,
+    /// Only report the commits up to this one instead of all undeployed commits
+    pub to_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseNotesOptions {
+    /// Environment the changes are not deployed to yet
+    pub env: String,
+    /// Environment the changes are deployed to, the newest commit is used if unset
+    pub to_env: Option<String>,
+    /// Where to look for ticket keys, `TicketSource::DEFAULT_PRECEDENCE` is used if empty
+    pub ticket_sources: Vec<TicketSource>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MarkDeployedOptions {
+    /// SHA deployed before the deployment being marked, the previous deployment of the history if
+    /// unset
+    pub from_sha: Option<String>,
+    /// SHA of the deployment being marked, the currently deployed SHA if unset
+    pub to_sha: Option<String>,
+    /// Status to move the deployed tickets to, e.g. `Released`
+    pub status: String,
+    /// Only print the planned transitions
+    pub dry_run: bool,
+    /// Comment on every ticket with the env, build, SHA and time of the deployment
+    pub comment: bool,
+    /// Build of the deployment mentioned in the comment, e.g. a CI build number
+    pub build: Option<String>,
+    /// Fix version to add the tickets to, created if missing
+    pub fix_version: Option<String>,
+    /// Where to look for ticket keys, `TicketSource::DEFAULT_PRECEDENCE` is used if empty
+    pub ticket_sources: Vec<TicketSource>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateReleaseOptions {
+    /// Tag to create on the deployed commit, e.g. `v1.2.0`
+    pub tag: String,
+    /// Name of the release, the tag if unset
+    pub name: Option<String>,
+    /// SHA of the previous release, the commit of the latest release tag if unset
+    pub from_sha: Option<String>,
+    /// SHA to release, the currently deployed SHA if unset
+    pub to_sha: Option<String>,
+    /// Only print the planned tag and release
+    pub dry_run: bool,
+    /// Where to look for ticket keys, `TicketSource::DEFAULT_PRECEDENCE` is used if empty
+    pub ticket_sources: Vec<TicketSource>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HistoryOptions {
+    /// How many of the newest deployments to list
+    pub limit: usize,
+    /// Only list the deployments at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only list the deployments before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Where to look for ticket keys, `TicketSource::DEFAULT_PRECEDENCE` is used if empty
+    pub ticket_sources: Vec<TicketSource>,
+}
+
+/// A deployment and what it shipped
+#[derive(Debug, Clone)]
+pub struct DeploymentRecord {
+    pub deployment: Deployment,
+    /// SHA deployed before, `None` for the oldest known deployment
+    pub previous_commit_sha: Option<String>,
+    /// Commits after the previous deployment up to this one, newest first
+    pub commits: Vec<GetCommitResult>,
+    pub ticket_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WhereOptions {
+    /// Environments to check, every environment of the config file if empty
+    pub envs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PromoteOptions {
+    /// SHA to deploy
+    pub to_sha: String,
+    /// Only print the planned config change and PR description
+    pub dry_run: bool,
+    /// Open the PR even if the release is on hold
+    pub force: bool,
+    /// Options of the report used as the PR description
+    pub report_options: ShowUndeployedCommitsOptions,
+}
+
+pub struct MarkDeployedResult {
+    pub output: Vec<String>,
+    /// Tickets that could not be moved
+    pub failed_tickets: Vec<String>,
+}
+
+/// Keep only the first (newest) commit of every PR
+///
+/// Commits without a PR are kept as is.
+pub fn group_commits_by_pr(commits: Vec<GetCommitResult>) -> Vec<GetCommitResult> {
+    let mut seen_pr_numbers: Vec<u64> = vec![];
+
+    commits
+        .into_iter()
+        .filter(|commit| match commit.pr_number {
+            Some(pr_number) if seen_pr_numbers.contains(&pr_number) => false,
+            Some(pr_number) => {
+                seen_pr_numbers.push(pr_number);
+                true
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Group the commits by the tickets they reference, in the order of the newest commit of each ticket
+///
+/// A commit referencing several tickets is listed under each of them.
+/// Commits without a ticket are left out, so are tickets unknown to the issue tracker.
+pub fn group_ticket_infos_by_ticket<'a>(
+    ticket_infos: &[TicketInfo<'a>],
+    issue_keys: &HashMap<String, Vec<IssueInfo>>,
+    deployed_commits: &HashMap<String, Vec<String>>,
+) -> Vec<TicketInfoGroup<'a>> {
+    let mut groups: Vec<TicketInfoGroup<'a>> = vec![];
+    for ticket_info in ticket_infos {
+        let issues = issue_keys
+            .get(ticket_info.commit_sha)
+            .map(|issues| issues.as_slice())
+            .unwrap_or_default();
+        for issue in issues {
+            match groups.iter_mut().find(|group| group.issue.key == issue.key) {
+                Some(group) => group.info_items.push(ticket_info.clone()),
+                None => groups.push(TicketInfoGroup {
+                    issue: issue.clone(),
+                    info_items: vec![ticket_info.clone()],
+                    deployed_commit_shas: deployed_commits
+                        .get(&issue.key)
+                        .cloned()
+                        .unwrap_or_default(),
+                }),
+            }
+        }
+    }
+
+    groups
+}
+
+/// Find the newest commit such that every commit up to and including it has a ready JIRA ticket
+/// Commits that are reverted within the range (and their reverts) do not block the cut point,
+/// neither do exempt commits, nor untracked commits if they are allowed. Commits on hold always
+/// block it, even if their tickets are ready.
+///
+/// Expects the commits to be ordered from the newest to the oldest, as returned by GitHub.
+/// Returns `None` if the oldest undeployed commit is not ready.
+pub fn find_safe_to_deploy_sha<'a>(
+    ticket_infos: &[TicketInfo<'a>],
+    untracked_policy: UntrackedPolicy,
+) -> Option<&'a str> {
+    ticket_infos
+        .iter()
+        .rev()
+        .take_while(|info| {
+            info.verdict != Verdict::Hold
+                && match info.ticket_category {
+                    TicketCategory::Tracked => info.ticket_ready || info.is_in_revert_pair(),
+                    TicketCategory::Untracked => {
+                        untracked_policy == UntrackedPolicy::Allow || info.is_in_revert_pair()
+                    }
+                    TicketCategory::Exempt => true,
+                }
+        })
+        .last()
+        .map(|info| info.commit_sha)
+}
+
+/// Ticket standing for a commit, and whether the commit is ready
+///
+/// A commit is only ready if every ticket it references is, the first ticket that is not ready
+/// stands for it.
+fn commit_ticket(tickets: &[IssueInfo]) -> (IssueInfo, bool) {
+    let ticket = tickets
+        .iter()
+        .find(|ticket| !ticket.ready)
+        .or(tickets.first())
+        .cloned()
+        .unwrap_or_default();
+    let ready = !tickets.is_empty() && tickets.iter().all(|ticket| ticket.ready);
+    (ticket, ready)
+}
+
+pub struct ShowUndeployedCommitsResult {
+    pub output: Vec<String>,
+    pub release_verdict: Verdict,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::github_service::MockGithubService;
+    use futures::FutureExt;
+
+    fn ticket_info(commit_sha: &str, ticket_ready: bool) -> TicketInfo<'_> {
+        TicketInfo {
+            commit_sha,
+            ticket_key: "FOO-1".to_string(),
+            ticket_ready,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_deployment_comment_marker() {
+        assert_eq!(
+            deployment_comment_marker("prod", Some("42"), "abc1234def"),
+            "Deployed to prod in build 42 (sha abc1234)"
+        );
+        assert_eq!(
+            deployment_comment_marker("staging", None, "abc1234def"),
+            "Deployed to staging (sha abc1234)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deployed_count_compares_each_sha_once() {
+        let mut github = MockGithubService::new();
+        github
+            .expect_is_ancestor()
+            .times(2)
+            .returning(|_, _, ancestor_sha, _| {
+                let is_ancestor = ancestor_sha == "aaa";
+                async move { Ok(is_ancestor) }.boxed()
+            });
+        let commit_shas = vec!["aaa".to_string(), "bbb".to_string()];
+        let mut deployed_counts = HashMap::new();
+
+        for _ in 0..2 {
+            let count = deployed_count(
+                &github,
+                "jrumjantsev",
+                "foo",
+                &commit_shas,
+                "deployed",
+                &mut deployed_counts,
+            )
+            .await
+            .unwrap();
+            assert_eq!(count, 1);
+        }
+    }
+
+    #[test]
+    fn test_group_commits_by_pr() {
+        let commit = |sha: &str, pr_number: Option<u64>| GetCommitResult {
+            sha: sha.to_string(),
+            pr_number,
+            ..Default::default()
+        };
+        let commits = vec![
+            commit("merge-42", Some(42)),
+            commit("branch-42-b", Some(42)),
+            commit("direct-push", None),
+            commit("branch-42-a", Some(42)),
+            commit("squash-41", Some(41)),
+        ];
+
+        let shas: Vec<String> = group_commits_by_pr(commits)
+            .into_iter()
+            .map(|commit| commit.sha)
+            .collect();
+
+        assert_eq!(shas, vec!["merge-42", "direct-push", "squash-41"]);
+    }
+
+    #[test]
+    fn test_group_ticket_infos_by_ticket() {
+        let issue = |key: &str| IssueInfo {
+            key: key.to_string(),
+            ..Default::default()
+        };
+        let ticket_infos = vec![
+            ticket_info("3", true),
+            ticket_info("2", true),
+            ticket_info("1", true),
+        ];
+        let issue_keys = HashMap::from([
+            ("3".to_string(), vec![issue("FOO-2")]),
+            ("2".to_string(), vec![issue("FOO-1"), issue("FOO-2")]),
+            ("1".to_string(), vec![]),
+        ]);
+        let deployed_commits = HashMap::from([("FOO-1".to_string(), vec!["0".to_string()])]);
+
+        let groups = group_ticket_infos_by_ticket(&ticket_infos, &issue_keys, &deployed_commits);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].issue.key, "FOO-2");
+        let shas: Vec<&str> = groups[0].info_items.iter().map(|i| i.commit_sha).collect();
+        assert_eq!(shas, vec!["3", "2"]);
+        assert!(groups[0].deployed_commit_shas.is_empty());
+        assert_eq!(groups[1].issue.key, "FOO-1");
+        assert_eq!(groups[1].deployed_commit_shas, vec!["0"]);
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha() {
+        // newest first
+        let ticket_infos = vec![
+            ticket_info("4", true),
+            ticket_info("3", false),
+            ticket_info("2", true),
+            ticket_info("1", true),
+        ];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            Some("2")
+        );
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha_on_hold() {
+        let ticket_infos = vec![
+            ticket_info("3", true),
+            TicketInfo {
+                verdict: Verdict::Hold,
+                ..ticket_info("2", true)
+            },
+            ticket_info("1", true),
+        ];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn test_commit_ticket_requires_every_ticket_ready() {
+        let ticket = |key: &str, ready| IssueInfo {
+            key: key.to_string(),
+            ready,
+            ..Default::default()
+        };
+
+        let (jira_info, ready) = commit_ticket(&[ticket("FOO-1", true), ticket("FOO-2", false)]);
+        assert_eq!(jira_info.key, "FOO-2");
+        assert!(!ready);
+
+        let (jira_info, ready) = commit_ticket(&[ticket("FOO-1", true), ticket("FOO-2", true)]);
+        assert_eq!(jira_info.key, "FOO-1");
+        assert!(ready);
+
+        assert!(!commit_ticket(&[]).1);
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha_revert_pair() {
+        let ticket_infos = vec![
+            ticket_info("3", true),
+            TicketInfo {
+                reverts: Some("1"),
+                ..ticket_info("2", false)
+            },
+            TicketInfo {
+                reverted_by: Some("2"),
+                ..ticket_info("1", false)
+            },
+        ];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            Some("3")
+        );
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha_untracked() {
+        let untracked = |commit_sha| TicketInfo {
+            ticket_category: TicketCategory::Untracked,
+            ..ticket_info(commit_sha, false)
+        };
+        let exempt = |commit_sha| TicketInfo {
+            ticket_category: TicketCategory::Exempt,
+            ..ticket_info(commit_sha, false)
+        };
+        let ticket_infos = vec![untracked("3"), exempt("2"), ticket_info("1", true)];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            Some("2")
+        );
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Allow),
+            Some("3")
+        );
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha_all_ready() {
+        let ticket_infos = vec![ticket_info("2", true), ticket_info("1", true)];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            Some("2")
+        );
+    }
+
+    #[test]
+    fn test_find_safe_to_deploy_sha_oldest_not_ready() {
+        let ticket_infos = vec![ticket_info("2", true), ticket_info("1", false)];
+
+        assert_eq!(
+            find_safe_to_deploy_sha(&ticket_infos, UntrackedPolicy::Warn),
+            None
+        );
+        assert_eq!(find_safe_to_deploy_sha(&[], UntrackedPolicy::Warn), None);
+    }
 }
@@ -0,0 +1,170 @@
+use crate::api::bitbucket_service::BitbucketServiceImpl;
+use crate::api::config_extraction_service::{
+    ConfigExtractionService, GithubConfigExtractionServiceImpl,
+};
+use crate::api::github_issues_service::GithubIssuesServiceImpl;
+use crate::api::github_service::{GithubService, GithubServiceImpl};
+use crate::api::gitlab_service::GitlabServiceImpl;
+use crate::api::issue_tracker_service::IssueTrackerService;
+use crate::api::jira_service::JiraServiceImpl;
+use crate::api::linear_service::LinearServiceImpl;
+use crate::api::local_git_service::LocalGitServiceImpl;
+use crate::api::metrics_service::{MetricsService, MetricsServiceImpl};
+use crate::api::release_gate_service::{
+    ReleaseGateService, ReleaseGateServiceImpl, UntrackedAllowlist,
+};
+use crate::api::release_notes_service::{ReleaseNotesService, ReleaseNotesServiceImpl};
+use crate::api::result_printer_service::{ResultPrinterService, ResultPrinterServiceImpl};
+use crate::api::revert_detection_service::{RevertDetectionService, RevertDetectionServiceImpl};
+use crate::api::ticket_extraction_service::{
+    GithubIssueTicketExtractionService, TicketExtractionService, TicketExtractionServiceImpl,
+};
+use crate::api::youtrack_service::YoutrackServiceImpl;
+use crate::config::{Config, IssueTracker, SourceControl};
+use anyhow::{anyhow, Context};
+
+/// Services of a project, wired from the source control, issue tracker and templates configured
+/// for it
+pub struct ProjectServices<'a> {
+    pub github_service: Box<dyn GithubService + 'a>,
+    pub ticket_extraction_service: Box<dyn TicketExtractionService + 'a>,
+    pub result_printer_service: Box<dyn ResultPrinterService + 'a>,
+    pub issue_tracker_service: Box<dyn IssueTrackerService + 'a>,
+    pub config_extraction_service: Box<dyn ConfigExtractionService + 'a>,
+    pub release_gate_service: Box<dyn ReleaseGateService + 'a>,
+    pub revert_detection_service: Box<dyn RevertDetectionService + 'a>,
+    pub release_notes_service: Box<dyn ReleaseNotesService + 'a>,
+    pub metrics_service: Box<dyn MetricsService + 'a>,
+}
+
+impl<'a> ProjectServices<'a> {
+    /// `default_ticket_prefixes` are used if `TICKET_PREFIXES_<PROJECT>` is not set
+    pub fn new(
+        owner_name: &str,
+        project: &str,
+        default_ticket_prefixes: &[&str],
+        config: &'a Config,
+    ) -> anyhow::Result<Self> {
+        let source_control_service = || -> anyhow::Result<Box<dyn GithubService>> {
+            Ok(match config.source_control(project) {
+                SourceControl::Github => Box::new(
+                    GithubServiceImpl::new(
+                        Some(config.github_server.as_str()),
+                        Some(config.github_token.as_str()),
+                    )
+                    .map_err(|err| anyhow!("{}", err))?,
+                ),
+                SourceControl::Gitlab => Box::new(
+                    GitlabServiceImpl::new(
+                        config.gitlab_server.as_deref(),
+                        config.gitlab_token.as_deref(),
+                    )
+                    .map_err(|err| anyhow!("{}", err))?,
+                ),
+                SourceControl::Bitbucket => Box::new(
+                    BitbucketServiceImpl::new(
+                        config.bitbucket_server.as_deref(),
+                        config.bitbucket_token.as_deref(),
+                    )
+                    .map_err(|err| anyhow!("{}", err))?,
+                ),
+            })
+        };
+
+        let issue_tracker = config.issue_tracker(project);
+        let issue_tracker_service: Box<dyn IssueTrackerService + 'a> = match issue_tracker {
+            IssueTracker::Jira => Box::new(JiraServiceImpl::from_config(config, project)),
+            IssueTracker::Linear => Box::new(
+                LinearServiceImpl::new(
+                    config.linear_server.as_deref(),
+                    config.linear_token.as_deref(),
+                    config.linear_ready_states.clone(),
+                )
+                .map_err(|err| anyhow!("{}", err))?,
+            ),
+            IssueTracker::Youtrack => Box::new(
+                YoutrackServiceImpl::new(
+                    config.youtrack_server.as_deref(),
+                    config.youtrack_token.as_deref(),
+                    config.youtrack_ready_field.as_deref(),
+                    config.youtrack_ready_values.clone(),
+                )
+                .map_err(|err| anyhow!("{}", err))?,
+            ),
+            IssueTracker::GithubIssues => Box::new(GithubIssuesServiceImpl::new(
+                source_control_service()?,
+                &config
+                    .github_issues_repo
+                    .clone()
+                    .unwrap_or(format!("{}/{}", owner_name, project)),
+                config.github_issues_ready_labels.clone(),
+                config.github_issues_ready_milestones.clone(),
+            )),
+        };
+        let ticket_extraction_service: Box<dyn TicketExtractionService + 'a> = match issue_tracker {
+            IssueTracker::GithubIssues => Box::new(GithubIssueTicketExtractionService::new()),
+            _ => {
+                let default_ticket_prefixes: Vec<String> = default_ticket_prefixes
+                    .iter()
+                    .map(|prefix| prefix.to_string())
+                    .collect();
+                Box::new(
+                    TicketExtractionServiceImpl::new(
+                        config
+                            .ticket_prefixes(project)
+                            .unwrap_or(&default_ticket_prefixes),
+                        &config.ticket_patterns,
+                        &config.ticket_exclusions,
+                    )
+                    .context("invalid ticket pattern")?,
+                )
+            }
+        };
+
+        let commits_service: Box<dyn GithubService + 'a> = match &config.local_repo {
+            Some(repo_path) => {
+                let pr_service = if config.local_repo_only {
+                    None
+                } else {
+                    Some(source_control_service()?)
+                };
+                Box::new(LocalGitServiceImpl::new(repo_path, pr_service))
+            }
+            None => source_control_service()?,
+        };
+
+        Ok(ProjectServices {
+            github_service: commits_service,
+            ticket_extraction_service,
+            result_printer_service: Box::new(
+                ResultPrinterServiceImpl::from_config(config)
+                    .context("failed to load report template")?,
+            ),
+            issue_tracker_service,
+            config_extraction_service: Box::new(GithubConfigExtractionServiceImpl::new(
+                source_control_service()?,
+            )),
+            release_gate_service: Box::new(
+                ReleaseGateServiceImpl::from_file(config.release_rules_file.as_deref())
+                    .context("failed to load release rules")?
+                    .with_untracked(
+                        config.untracked_policy,
+                        UntrackedAllowlist {
+                            commit_types: config.untracked_allowed_types.clone(),
+                            authors: config.untracked_allowed_authors.clone(),
+                        },
+                    ),
+            ),
+            revert_detection_service: Box::new(RevertDetectionServiceImpl::new()),
+            release_notes_service: Box::new(
+                ReleaseNotesServiceImpl::from_file(
+                    config
+                        .release_notes_template(project)
+                        .map(|path| path.as_str()),
+                )
+                .context("failed to load release notes template")?,
+            ),
+            metrics_service: Box::new(MetricsServiceImpl::new()),
+        })
+    }
+}
@@ -3,13 +3,23 @@ mod config;
 mod constants;
 
 mod api {
+    pub mod bitbucket_service;
     pub mod config_extraction_service;
     pub mod errors;
+    pub mod github_issues_service;
     pub mod github_service;
+    pub mod gitlab_service;
+    pub mod issue_tracker_service;
     pub mod jira_service;
+    pub mod linear_service;
+    pub mod local_git_service;
+    pub mod metrics_service;
+    pub mod release_gate_service;
+    pub mod release_notes_service;
     pub mod result_printer_service;
+    pub mod revert_detection_service;
     pub mod ticket_extraction_service;
-    pub mod version_service;
+    pub mod youtrack_service;
 }
 mod domain {
     pub mod to_deploy {
@@ -17,18 +27,22 @@ mod domain {
             pub mod bar_info_gathering_service;
             pub mod foo_info_gathering_service;
             pub mod info_gathering_service;
+            pub mod project_services;
         }
     }
-    pub mod doctor {
-        pub mod doctor_app;
-    }
 }
 
-use crate::cli::{Cli, Commands};
+use crate::api::release_gate_service::Verdict;
+use crate::api::result_printer_service::ReportFormat;
+use crate::cli::{BackendArgs, Cli, Commands};
 use crate::config::Config;
 use crate::domain::to_deploy::services::bar_info_gathering_service::BarInfoGatheringService;
 use crate::domain::to_deploy::services::foo_info_gathering_service::FooInfoGatheringService;
-use crate::domain::to_deploy::services::info_gathering_service::InfoGatheringService;
+use crate::domain::to_deploy::services::info_gathering_service::{
+    CreateReleaseOptions, HistoryOptions, InfoGatheringService, MarkDeployedOptions,
+    PromoteOptions, ReleaseNotesOptions, ShowUndeployedCommitsOptions, WhereOptions,
+};
+use chrono::{Duration, NaiveTime, Utc};
 use clap::Parser;
 
 #[tokio::main]
@@ -38,40 +52,370 @@ async fn main() {
 
     let cli = Cli::parse();
     match cli.command {
-        Commands::ToDeploy(args) => match args.project.as_str() {
-            constants::PROJECT_FOO_WEB => {
-                if let Ok(output) = FooInfoGatheringService::new(&Config::new())
-                    .show_undeployed_commits(
-                        args.owner.as_str(),
-                        args.project.as_str(),
-                        args.env.as_str(),
-                    )
-                    .await
-                {
+        Commands::ToDeploy(args) => {
+            let mut config = config();
+            if args.rules.is_some() {
+                config.release_rules_file = args.rules.clone();
+            }
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            if let Some(untracked_policy) = args.untracked {
+                config.untracked_policy = untracked_policy;
+            }
+            if let Some(report_format) = args.format {
+                config.report_format = report_format;
+                config.report_template = None;
+            }
+            if args.template.is_some() {
+                config.report_template = args.template.clone();
+            }
+            let options = ShowUndeployedCommitsOptions {
+                first_parent: args.first_parent,
+                group_by: args.group_by,
+                ticket_sources: args
+                    .ticket_sources
+                    .clone()
+                    .unwrap_or(config.ticket_sources.clone()),
+                ticket_lookback_days: config.ticket_lookback_days,
+                to_sha: None,
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .show_undeployed_commits(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.env.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(result) => {
+                    for line in result.output {
+                        println!("{}", line);
+                    }
+                    exit_with_verdict(result.release_verdict);
+                }
+                Err(err) => {
+                    eprintln!("Error listing the undeployed commits: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::ReleaseNotes(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            if let Some(template) = &args.template {
+                config.set_release_notes_template(&args.project, template);
+            }
+            let options = ReleaseNotesOptions {
+                env: args.env.clone(),
+                to_env: args.to_env.clone(),
+                ticket_sources: config.ticket_sources.clone(),
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .release_notes(args.owner.as_str(), args.project.as_str(), &options)
+                .await
+            {
+                Ok(release_notes) => print!("{}", release_notes),
+                Err(err) => {
+                    eprintln!("Error generating the release notes: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::MarkDeployed(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            let status = match args
+                .status
+                .clone()
+                .or(config.jira_deployed_status(&args.env).cloned())
+            {
+                Some(status) => status,
+                None => {
+                    eprintln!(
+                        "No deployed status for {}, set JIRA_DEPLOYED_STATUS_{} or --status",
+                        args.env,
+                        args.env.to_uppercase().replace('-', "_")
+                    );
+                    std::process::exit(1);
+                }
+            };
+            let options = MarkDeployedOptions {
+                from_sha: args.from.clone(),
+                to_sha: args.to.clone(),
+                status,
+                dry_run: args.dry_run,
+                comment: args.comment,
+                build: args.build.clone(),
+                fix_version: args.fix_version.clone(),
+                ticket_sources: config.ticket_sources.clone(),
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .mark_deployed(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.env.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(result) => {
+                    for line in result.output {
+                        println!("{}", line);
+                    }
+                    if !result.failed_tickets.is_empty() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Error marking the deployment: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Release(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            let options = CreateReleaseOptions {
+                tag: args.tag.clone(),
+                name: args.name.clone(),
+                from_sha: args.from.clone(),
+                to_sha: args.to.clone(),
+                dry_run: args.dry_run,
+                ticket_sources: config.ticket_sources.clone(),
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .create_release(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.env.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(output) => {
+                    for line in output {
+                        println!("{}", line);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Error creating the release: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Promote(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            config.report_format = args.format.unwrap_or(ReportFormat::Markdown);
+            config.report_template = args.template.clone();
+            let options = PromoteOptions {
+                to_sha: args.to.clone(),
+                dry_run: args.dry_run,
+                force: args.force,
+                report_options: ShowUndeployedCommitsOptions {
+                    group_by: args.group_by,
+                    ticket_sources: config.ticket_sources.clone(),
+                    ticket_lookback_days: config.ticket_lookback_days,
+                    ..Default::default()
+                },
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .promote(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.env.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(output) => {
+                    for line in output {
+                        println!("{}", line);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Error promoting {}: {}", args.project, err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::History(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            let options = HistoryOptions {
+                limit: args.limit,
+                ticket_sources: config.ticket_sources.clone(),
+                ..Default::default()
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .history(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.env.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(output) => {
                     for line in output {
                         println!("{}", line);
                     }
                 }
+                Err(err) => {
+                    eprintln!("Error fetching the deployment history: {}", err);
+                    std::process::exit(1);
+                }
             }
-            constants::PROJECT_BAR_WEB => {
-                if let Ok(output) = BarInfoGatheringService::new(&Config::new())
-                    .show_undeployed_commits(
+        }
+        Commands::Where(args) => {
+            let mut config = config();
+            apply_backend_args(&mut config, &args.project, &args.backend);
+            let options = WhereOptions {
+                envs: args.envs.clone().unwrap_or_default(),
+            };
+
+            let service = info_gathering_service(&args.owner, &args.project, &config);
+            match service
+                .where_deployed(
+                    args.owner.as_str(),
+                    args.project.as_str(),
+                    args.target.as_str(),
+                    &options,
+                )
+                .await
+            {
+                Ok(output) => {
+                    for line in output {
+                        println!("{}", line);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Error looking up {}: {}", args.target, err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Metrics(args) => {
+            let mut config = config();
+            for project in &args.project {
+                apply_backend_args(&mut config, project, &args.backend);
+            }
+            let until = match args.until {
+                // The whole last day is in the range
+                Some(until) => {
+                    until.and_time(NaiveTime::MIN).and_utc()
+                        + Duration::try_days(1).unwrap_or_default()
+                }
+                None => Utc::now(),
+            };
+            let since = match args.since {
+                Some(since) => since.and_time(NaiveTime::MIN).and_utc(),
+                None => until - Duration::try_days(30).unwrap_or_default(),
+            };
+
+            let mut metrics = vec![];
+            for project in &args.project {
+                let service = info_gathering_service(&args.owner, project, &config);
+                match service
+                    .metrics(
                         args.owner.as_str(),
-                        args.project.as_str(),
+                        project,
                         args.env.as_str(),
+                        since,
+                        until,
                     )
                     .await
                 {
-                    for line in output {
-                        println!("{}", line);
+                    Ok(project_metrics) => metrics.push(project_metrics),
+                    Err(err) => {
+                        eprintln!("Error computing the metrics of {}: {}", project, err);
+                        std::process::exit(1);
                     }
                 }
             }
-            // Add more projects if needed
-            _ => println!("Project not found: {}", args.project),
-        },
+
+            match args.format.render(&metrics) {
+                Ok(output) => print!("{}", output),
+                Err(err) => {
+                    eprintln!("Error rendering the metrics: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
         Commands::Doctor(_) => {
             todo!("Another application logic here that will be implemented in the future for analyzing JIRA tickets that are in the wrong status.")
         }
     }
 }
+
+/// Configuration read from the environment, exits if it is invalid
+fn config() -> Config {
+    Config::new().unwrap_or_else(|err| {
+        eprintln!("{:#}", err);
+        std::process::exit(1);
+    })
+}
+
+/// Services of a project, exits if the project is unknown or its services cannot be built
+fn info_gathering_service<'a>(
+    owner_name: &str,
+    project: &str,
+    config: &'a Config,
+) -> Box<dyn InfoGatheringService<'a> + 'a> {
+    project_services(owner_name, project, config).unwrap_or_else(|err| {
+        eprintln!("{:#}", err);
+        std::process::exit(1);
+    })
+}
+
+/// Services of a project, an error if the project is unknown or its services cannot be built
+fn project_services<'a>(
+    owner_name: &str,
+    project: &str,
+    config: &'a Config,
+) -> anyhow::Result<Box<dyn InfoGatheringService<'a> + 'a>> {
+    match project {
+        constants::PROJECT_FOO_WEB => {
+            Ok(Box::new(FooInfoGatheringService::new(owner_name, config)?))
+        }
+        constants::PROJECT_BAR_WEB => {
+            Ok(Box::new(BarInfoGatheringService::new(owner_name, config)?))
+        }
+        // Add more projects if needed
+        _ => Err(anyhow::anyhow!("Project not found: {}", project)),
+    }
+}
+
+/// Apply the `--local-repo`, `--local-repo-only`, `--source-control` and `--issue-tracker`
+/// overrides of a project
+fn apply_backend_args(config: &mut Config, project: &str, args: &BackendArgs) {
+    if args.local_repo.is_some() {
+        config.local_repo = args.local_repo.clone();
+        config.local_repo_only = args.local_repo_only;
+    }
+    if let Some(source_control) = args.source_control {
+        config.set_source_control(project, source_control);
+    }
+    if let Some(issue_tracker) = args.issue_tracker {
+        config.set_issue_tracker(project, issue_tracker);
+    }
+}
+
+/// Exit with a non-zero code if the release is on hold, so that CI can block the promotion
+fn exit_with_verdict(verdict: Verdict) {
+    if verdict == Verdict::Hold {
+        std::process::exit(2);
+    }
+}